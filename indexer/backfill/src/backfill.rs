@@ -0,0 +1,406 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use entity::{
+    block::EraValue,
+    execution_plan,
+    prelude::*,
+    sea_orm::{
+        prelude::*, Condition, DatabaseTransaction, QueryOrder, QuerySelect, Set, TransactionTrait,
+    },
+};
+use serde::{Deserialize, Serialize};
+use tasks::{
+    byron::byron_executor::process_byron_block,
+    dsl::database_task::{BlockGlobalInfo, TaskRegistryEntry},
+    execution_plan::ExecutionPlan,
+    multiera::multiera_executor::process_multiera_block,
+    task_ranges::add_task_range,
+    utils::{blake2b256, find_task_registry_entry, SlotTimeParams, TaskPerfAggregator},
+};
+
+/// Inclusive bounds on the blocks to backfill
+pub struct BlockRange {
+    pub from_height: Option<i32>,
+    pub to_height: Option<i32>,
+    pub from_slot: Option<i32>,
+    pub to_slot: Option<i32>,
+}
+
+impl BlockRange {
+    fn to_condition(&self) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(from_height) = self.from_height {
+            condition = condition.add(BlockColumn::Height.gte(from_height));
+        }
+        if let Some(to_height) = self.to_height {
+            condition = condition.add(BlockColumn::Height.lte(to_height));
+        }
+        if let Some(from_slot) = self.from_slot {
+            condition = condition.add(BlockColumn::Slot.gte(from_slot));
+        }
+        if let Some(to_slot) = self.to_slot {
+            condition = condition.add(BlockColumn::Slot.lte(to_slot));
+        }
+        condition
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    tasks: Vec<String>,
+    /// id of the last block whose batch was committed
+    last_block_id: i32,
+}
+
+/// Creates an execution plan containing only the tasks to backfill and their dependencies
+/// note: dependencies are made readonly so they don't insert data again.
+/// Dependencies without a readonly mode have to be backfilled as well (listed in `tasks`)
+pub fn build_backfill_plan(plan_path: &str, tasks: &[String]) -> anyhow::Result<ExecutionPlan> {
+    let full_plan = ExecutionPlan::load_from_file(plan_path, false)?;
+
+    let mut to_include = BTreeSet::<String>::new();
+    let mut to_check: Vec<String> = tasks.to_vec();
+    while let Some(task_name) = to_check.pop() {
+        if !full_plan.0.contains_key(&task_name) {
+            return Err(anyhow!(
+                "Task {} isn't part of the execution plan {}",
+                task_name,
+                plan_path
+            ));
+        }
+        let entry = find_task_registry_entry(&task_name)
+            .ok_or_else(|| anyhow!("Could not find task named {}", task_name))?;
+        if let TaskRegistryEntry::Genesis(_) = entry {
+            return Err(anyhow!(
+                "{} is a genesis task and can't be backfilled",
+                task_name
+            ));
+        }
+        for &dependency in entry.get_dependencies() {
+            if !to_include.contains(dependency) {
+                to_check.push(dependency.to_string());
+            }
+        }
+        to_include.insert(task_name);
+    }
+
+    let mut backfill_plan = toml::value::Table::new();
+    let mut not_readonly = vec![];
+    // note: the full plan is already sorted by dependencies so the order is kept
+    for (task_name, val) in full_plan.0.iter() {
+        if !to_include.contains(task_name) {
+            continue;
+        }
+        let mut val = val.clone();
+        if !tasks.contains(task_name) {
+            match val
+                .as_table_mut()
+                .and_then(|props| props.get_mut("readonly"))
+            {
+                Some(readonly) => *readonly = toml::Value::Boolean(true),
+                None => not_readonly.push(task_name.clone()),
+            }
+        }
+        backfill_plan.insert(task_name.clone(), val);
+    }
+    if !not_readonly.is_empty() {
+        // running them again would insert their rows a second time
+        return Err(anyhow!(
+            "Dependencies {:?} have no readonly mode. Add them with --task if their data should be inserted again",
+            not_readonly
+        ));
+    }
+
+    let backfill_plan = ExecutionPlan(backfill_plan);
+    backfill_plan
+        .validate()
+        .map_err(|errors| anyhow!("Invalid backfill plan:\n{}", errors))?;
+    tracing::info!(
+        "Backfilling with tasks {:?}",
+        backfill_plan.0.keys().collect::<Vec<_>>()
+    );
+
+    Ok(backfill_plan)
+}
+
+fn read_checkpoint(path: &str, tasks: &[String]) -> anyhow::Result<Option<Checkpoint>> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let checkpoint: Checkpoint = serde_json::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|err| anyhow!("Can't parse checkpoint {}: {}", path, err))?;
+    if checkpoint.tasks != tasks {
+        return Err(anyhow!(
+            "Checkpoint {} was created to backfill {:?}. Delete it to start a new backfill",
+            path,
+            checkpoint.tasks
+        ));
+    }
+    Ok(Some(checkpoint))
+}
+
+pub async fn start_backfill(
+    conn: DatabaseConnection,
+    exec_plan: ExecutionPlan,
+    tasks: &[String],
+    range: BlockRange,
+    slot_time: Option<SlotTimeParams>,
+    batch_size: u64,
+    checkpoint_path: &str,
+) -> anyhow::Result<()> {
+    let mut last_block_id = match read_checkpoint(checkpoint_path, tasks)? {
+        Some(checkpoint) => {
+            tracing::info!(
+                "Resuming backfill after block id {}",
+                checkpoint.last_block_id
+            );
+            checkpoint.last_block_id
+        }
+        None => -1,
+    };
+
+    let block_count = Block::find()
+        .filter(range.to_condition())
+        .filter(BlockColumn::Id.gt(last_block_id))
+        .count(&conn)
+        .await?;
+    tracing::info!("{} blocks to backfill", block_count);
+
+    let backfill_start = std::time::Instant::now();
+    let perf_aggregator = Arc::new(Mutex::new(TaskPerfAggregator::default()));
+    let mut processed: u64 = 0;
+
+    loop {
+        let blocks = Block::find()
+            .filter(range.to_condition())
+            .filter(BlockColumn::Id.gt(last_block_id))
+            .order_by_asc(BlockColumn::Id)
+            .limit(batch_size)
+            .all(&conn)
+            .await?;
+        let last_block = match blocks.last() {
+            Some(last_block) => last_block.clone(),
+            None => break,
+        };
+
+        let txn = conn.begin().await?;
+        // note: recorded before the blocks are processed so that the tasks see them as processed,
+        // like when they are run by the indexer
+        record_backfilled_blocks(
+            &txn,
+            &exec_plan,
+            tasks,
+            blocks.first().unwrap().height,
+            last_block.height,
+        )
+        .await?;
+        for block in blocks.iter() {
+            backfill_block(&txn, block, &exec_plan, slot_time, perf_aggregator.clone()).await?;
+        }
+        txn.commit().await?;
+
+        last_block_id = last_block.id;
+        processed += blocks.len() as u64;
+        let checkpoint = Checkpoint {
+            tasks: tasks.to_vec(),
+            last_block_id,
+        };
+        std::fs::write(checkpoint_path, serde_json::to_string(&checkpoint)?)?;
+
+        tracing::info!(
+            "blocks: {} / {} ({:.1}%), at height {} (slot {})",
+            processed,
+            block_count,
+            (100.0 * processed as f64) / (block_count as f64),
+            last_block.height,
+            last_block.slot
+        );
+    }
+
+    tracing::debug!(
+        "Task-wise time spent:\n{:#?}",
+        perf_aggregator.lock().unwrap()
+    );
+    tracing::info!("Backfill completed after {:?}", backfill_start.elapsed());
+    if Path::new(checkpoint_path).exists() {
+        std::fs::remove_file(checkpoint_path)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the blocks to the ranges of the backfilled tasks and to the execution plans recorded for them,
+/// so the metadata of the database shows these blocks as processed by the tasks
+async fn record_backfilled_blocks(
+    txn: &DatabaseTransaction,
+    exec_plan: &ExecutionPlan,
+    tasks: &[String],
+    start_height: i32,
+    end_height: i32,
+) -> anyhow::Result<()> {
+    for task_name in tasks {
+        add_task_range(
+            txn,
+            task_name,
+            &exec_plan.0[task_name],
+            start_height,
+            end_height,
+        )
+        .await?;
+    }
+    add_tasks_to_execution_plans(txn, exec_plan, tasks, start_height, end_height).await
+}
+
+/// Adds the tasks to the execution plans recorded for the blocks from `start_height` to `end_height`,
+/// so that the indexer doesn't see them as added to its plan when they are part of it.
+/// Plans recorded for more blocks than these are split
+async fn add_tasks_to_execution_plans(
+    txn: &DatabaseTransaction,
+    exec_plan: &ExecutionPlan,
+    tasks: &[String],
+    start_height: i32,
+    end_height: i32,
+) -> anyhow::Result<()> {
+    // the plan being used by the indexer (if any) has no end height
+    let latest_height = Block::find()
+        .order_by_desc(BlockColumn::Id)
+        .one(txn)
+        .await?
+        .map(|block| block.height)
+        .unwrap_or(end_height);
+    let recorded_plans = execution_plan::Entity::find()
+        .filter(ExecutionPlanColumn::StartHeight.lte(end_height))
+        .filter(
+            Condition::any()
+                .add(ExecutionPlanColumn::EndHeight.is_null())
+                .add(ExecutionPlanColumn::EndHeight.gte(start_height)),
+        )
+        .order_by_asc(ExecutionPlanColumn::StartHeight)
+        .all(txn)
+        .await?;
+
+    for recorded_plan in recorded_plans {
+        let mut plan = ExecutionPlan::from_toml(&recorded_plan.plan)?;
+        for task_name in tasks {
+            plan.0
+                .insert(task_name.clone(), exec_plan.0[task_name].clone());
+        }
+        plan.sort_tasks(false)
+            .map_err(|errors| anyhow!("Can't add the tasks to the recorded plan:\n{}", errors))?;
+        let plan = plan.to_toml();
+        let hash = blake2b256(plan.as_bytes()).to_vec();
+        if hash == recorded_plan.hash {
+            continue;
+        }
+
+        let overlap_start = std::cmp::max(recorded_plan.start_height, start_height);
+        let overlap_end = std::cmp::min(
+            recorded_plan.end_height.unwrap_or(latest_height),
+            end_height,
+        );
+        let split = |start_height: i32, end_height: Option<i32>| execution_plan::ActiveModel {
+            hash: Set(recorded_plan.hash.clone()),
+            plan: Set(recorded_plan.plan.clone()),
+            carp_version: Set(recorded_plan.carp_version.clone()),
+            start_height: Set(start_height),
+            end_height: Set(end_height),
+            ..Default::default()
+        };
+        if recorded_plan.start_height < overlap_start {
+            split(recorded_plan.start_height, Some(overlap_start - 1))
+                .insert(txn)
+                .await?;
+        }
+        let end = if recorded_plan.end_height.unwrap_or(latest_height) > overlap_end {
+            split(overlap_end + 1, recorded_plan.end_height)
+                .insert(txn)
+                .await?;
+            Some(overlap_end)
+        } else {
+            recorded_plan.end_height
+        };
+
+        // the blocks of the previous batch are usually recorded with the same plan
+        let previous = execution_plan::Entity::find()
+            .filter(ExecutionPlanColumn::EndHeight.eq(overlap_start - 1))
+            .filter(ExecutionPlanColumn::Hash.eq(hash.clone()))
+            .one(txn)
+            .await?;
+        match previous {
+            Some(previous) => {
+                execution_plan::Entity::delete_by_id(recorded_plan.id)
+                    .exec(txn)
+                    .await?;
+                let mut previous: execution_plan::ActiveModel = previous.into();
+                previous.end_height = Set(end);
+                previous.update(txn).await?;
+            }
+            None => {
+                let mut recorded_plan: execution_plan::ActiveModel = recorded_plan.into();
+                recorded_plan.hash = Set(hash);
+                recorded_plan.plan = Set(plan);
+                recorded_plan.start_height = Set(overlap_start);
+                recorded_plan.end_height = Set(end);
+                recorded_plan.update(txn).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn backfill_block(
+    txn: &DatabaseTransaction,
+    block: &BlockModel,
+    exec_plan: &ExecutionPlan,
+    slot_time: Option<SlotTimeParams>,
+    perf_aggregator: Arc<Mutex<TaskPerfAggregator>>,
+) -> anyhow::Result<()> {
+    let payload = match &block.payload {
+        Some(payload) if !payload.is_empty() => payload,
+        _ => {
+            return Err(anyhow!(
+                "Block {} at height {} has no payload. Blocks need to be indexed with include_payload=true to be backfilled",
+                hex::encode(&block.hash),
+                block.height
+            ))
+        }
+    };
+    let multi_block = cml_multi_era::MultiEraBlock::from_explicit_network_cbor_bytes(payload)
+        .map_err(|err| anyhow!("Can't parse block {}: {:?}", hex::encode(&block.hash), err))?;
+    let cbor_hex = hex::encode(payload);
+
+    let block_global_info = BlockGlobalInfo {
+        era: EraValue::try_from(block.era).map_err(|_| anyhow!("Unknown era {}", block.era))?,
+        epoch: Some(block.epoch as u64),
+        // note: not stored in the database & not used by any task
+        epoch_slot: None,
+        slot_time,
+    };
+
+    match &multi_block {
+        cml_multi_era::MultiEraBlock::Byron(_byron) => {
+            process_byron_block(
+                txn,
+                (&cbor_hex, &multi_block, &block_global_info),
+                exec_plan,
+                perf_aggregator,
+            )
+            .await?
+        }
+        _ => {
+            process_multiera_block(
+                txn,
+                (&cbor_hex, &multi_block, &block_global_info),
+                exec_plan,
+                perf_aggregator,
+            )
+            .await?
+        }
+    }
+
+    Ok(())
+}
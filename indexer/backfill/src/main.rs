@@ -0,0 +1,106 @@
+mod backfill;
+
+use clap::Parser;
+use dotenv::dotenv;
+use entity::sea_orm::Database;
+use tasks::utils::SlotTimeParams;
+use tracing_subscriber::prelude::*;
+
+use crate::backfill::BlockRange;
+
+#[derive(Parser, Debug)]
+#[clap(version)]
+pub struct Args {
+    /// Path of the execution plan the tasks & their configuration are taken from
+    #[clap(short, long, default_value = "execution_plans/default.toml")]
+    plan: String,
+
+    /// Task to backfill (can be repeated). Its dependencies are run as well
+    #[clap(long = "task", required = true)]
+    tasks: Vec<String>,
+
+    /// First block height to backfill (inclusive)
+    #[clap(long)]
+    from_height: Option<i32>,
+
+    /// Last block height to backfill (inclusive)
+    #[clap(long)]
+    to_height: Option<i32>,
+
+    /// First slot to backfill (inclusive)
+    #[clap(long)]
+    from_slot: Option<i32>,
+
+    /// Last slot to backfill (inclusive)
+    #[clap(long)]
+    to_slot: Option<i32>,
+
+    /// Network of the blocks (mainnet, preprod, preview, testnet or sanchonet).
+    /// Only needed by tasks that use the time of the blocks (ex: MultieraDexCandleTask)
+    #[clap(long)]
+    network: Option<String>,
+
+    /// Number of blocks processed in a single database transaction between checkpoints
+    #[clap(long, default_value = "100")]
+    batch_size: u64,
+
+    /// File used to save progress so the backfill can be resumed after an interruption
+    #[clap(long, default_value = "backfill_checkpoint.json")]
+    checkpoint: String,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    // Start logging setup block
+    let fmt_layer = tracing_subscriber::fmt::layer().with_test_writer();
+
+    let sqlx_filter = tracing_subscriber::filter::Targets::new()
+        // sqlx logs every SQL query and how long it took which is very noisy
+        .with_target("sqlx", tracing::Level::WARN)
+        .with_default(tracing_subscriber::fmt::Subscriber::DEFAULT_MAX_LEVEL);
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(sqlx_filter)
+        .init();
+    // End logging setup block
+
+    tracing::info!("{}", "Starting Carp backfill");
+
+    dotenv().ok();
+
+    let postgres_url = std::env::var("DATABASE_URL").expect("env DATABASE_URL not found");
+
+    let exec_plan = backfill::build_backfill_plan(&args.plan, &args.tasks)?;
+    let slot_time = match &args.network {
+        Some(network) => Some(
+            SlotTimeParams::for_network(network)
+                .ok_or_else(|| anyhow::anyhow!("Unknown network {network}"))?,
+        ),
+        None => None,
+    };
+
+    tracing::info!("{}", "Connecting to database...");
+    let conn = Database::connect(&postgres_url).await?;
+
+    let range = BlockRange {
+        from_height: args.from_height,
+        to_height: args.to_height,
+        from_slot: args.from_slot,
+        to_slot: args.to_slot,
+    };
+    backfill::start_backfill(
+        conn,
+        exec_plan,
+        &args.tasks,
+        range,
+        slot_time,
+        args.batch_size,
+        &args.checkpoint,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// History of the hot key authorizations and resignations of constitutional committee members
+/// `hot_credential` is null for resignations
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "CommitteeAuthorization")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    /// cbor of the cold credential of the committee member
+    pub cold_credential: Vec<u8>,
+    /// cbor of the hot credential used to vote (null for resignations)
+    pub hot_credential: Option<Vec<u8>>,
+    #[sea_orm(column_type = "BigInteger")]
+    pub tx_id: i64,
+    pub anchor_url: Option<String>,
+    pub anchor_hash: Option<Vec<u8>>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::TxId",
+        to = "super::transaction::Column::Id"
+    )]
+    Transaction,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
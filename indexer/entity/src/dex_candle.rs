@@ -0,0 +1,62 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// OHLCV aggregate of the swaps of a pair during a time interval
+/// Prices are the amount of asset2 paid for one unit of asset1 (in on-chain units)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "DexCandle")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    pub dex: i32,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub asset1_id: Option<i64>,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub asset2_id: Option<i64>,
+    /// length of the candle in seconds
+    #[sea_orm(column_type = "BigInteger")]
+    pub interval: i64,
+    /// unix time (in seconds) at which the candle starts
+    #[sea_orm(column_type = "BigInteger")]
+    pub bucket_start: i64,
+    #[sea_orm(column_type = "Double")]
+    pub open: f64,
+    #[sea_orm(column_type = "Double")]
+    pub high: f64,
+    #[sea_orm(column_type = "Double")]
+    pub low: f64,
+    #[sea_orm(column_type = "Double")]
+    pub close: f64,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub volume1: u64,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub volume2: u64,
+    /// last block with a swap in the candle
+    /// reset to null when the block is rolled back so the candle gets recomputed
+    #[sea_orm(nullable)]
+    pub last_block_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::native_asset::Entity",
+        from = "Column::Asset1Id",
+        to = "super::native_asset::Column::Id"
+    )]
+    Asset1,
+    #[sea_orm(
+        belongs_to = "super::native_asset::Entity",
+        from = "Column::Asset2Id",
+        to = "super::native_asset::Column::Id"
+    )]
+    Asset2,
+    #[sea_orm(
+        belongs_to = "super::block::Entity",
+        from = "Column::LastBlockId",
+        to = "super::block::Column::Id"
+    )]
+    Block,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -57,6 +57,9 @@ pub enum Dex {
     WingRidersV1,
     SundaeSwapV1,
     MinSwapV1,
+    MinSwapV2,
+    SundaeSwapV3,
+    WingRidersV2,
 }
 
 impl From<Dex> for i32 {
@@ -65,6 +68,9 @@ impl From<Dex> for i32 {
             Dex::WingRidersV1 => 0,
             Dex::SundaeSwapV1 => 1,
             Dex::MinSwapV1 => 2,
+            Dex::MinSwapV2 => 3,
+            Dex::SundaeSwapV3 => 4,
+            Dex::WingRidersV2 => 5,
         }
     }
 }
@@ -0,0 +1,56 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// History of the registrations, updates and retirements of DReps
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "DrepRegistration")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    /// cbor of the credential (same format as `StakeDelegationDrepCredentialRelation::drep_credential`)
+    pub drep_credential: Vec<u8>,
+    #[sea_orm(column_type = "BigInteger")]
+    pub tx_id: i64,
+    /// see `DrepEvent`
+    pub event: i32,
+    /// deposit paid on registration or refunded on retirement (null for updates)
+    #[sea_orm(column_type = "BigUnsigned", nullable)]
+    pub deposit: Option<u64>,
+    pub anchor_url: Option<String>,
+    pub anchor_hash: Option<Vec<u8>>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::TxId",
+        to = "super::transaction::Column::Id"
+    )]
+    Transaction,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DrepEvent {
+    Registration,
+    Update,
+    Retirement,
+}
+
+impl From<DrepEvent> for i32 {
+    fn from(item: DrepEvent) -> Self {
+        match item {
+            DrepEvent::Registration => 0,
+            DrepEvent::Update => 1,
+            DrepEvent::Retirement => 2,
+        }
+    }
+}
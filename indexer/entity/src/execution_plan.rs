@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Execution plans used to index the database, and the range of blocks they were used for
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "ExecutionPlan")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub hash: Vec<u8>,
+    /// toml content of the plan (after sorting tasks & adding missing dependencies)
+    pub plan: String,
+    pub carp_version: String,
+    /// height of the first block indexed with this plan
+    pub start_height: i32,
+    /// height of the last block indexed with this plan (None if it's the plan currently in use)
+    pub end_height: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,70 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "GovernanceProposal")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    #[sea_orm(column_type = "BigInteger")]
+    pub tx_id: i64,
+    /// index of the proposal procedure in the transaction (also the index of its gov action id)
+    #[sea_orm(column_type = "BigInteger")]
+    pub index: i64,
+    /// cbor of the gov action id created by the proposal (same format as `GovernanceVote::gov_action_id`)
+    pub gov_action_id: Vec<u8>,
+    /// hash of the transaction of the gov action id, to look up proposals without decoding `gov_action_id`
+    pub action_tx_hash: Vec<u8>,
+    /// see `GovActionType`
+    pub action_type: i32,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub deposit: u64,
+    pub reward_address: Vec<u8>,
+    pub anchor_url: String,
+    pub anchor_hash: Vec<u8>,
+    pub gov_action: Vec<u8>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::TxId",
+        to = "super::transaction::Column::Id"
+    )]
+    Transaction,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Values follow the tags of the gov actions in the Conway CDDL
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GovActionType {
+    ParameterChange,
+    HardForkInitiation,
+    TreasuryWithdrawals,
+    NoConfidence,
+    UpdateCommittee,
+    NewConstitution,
+    Info,
+}
+
+impl From<GovActionType> for i32 {
+    fn from(item: GovActionType) -> Self {
+        match item {
+            GovActionType::ParameterChange => 0,
+            GovActionType::HardForkInitiation => 1,
+            GovActionType::TreasuryWithdrawals => 2,
+            GovActionType::NoConfidence => 3,
+            GovActionType::UpdateCommittee => 4,
+            GovActionType::NewConstitution => 5,
+            GovActionType::Info => 6,
+        }
+    }
+}
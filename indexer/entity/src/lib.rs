@@ -1,8 +1,10 @@
 pub mod address;
+pub mod address_balance;
 pub mod address_credential;
 pub mod block;
 pub mod prelude;
 pub mod stake_credential;
+pub mod stake_credential_balance;
 pub mod transaction;
 pub mod transaction_input;
 pub mod transaction_output;
@@ -12,13 +14,28 @@ pub use sea_orm;
 pub mod asset_mint;
 pub mod asset_utxos;
 pub mod cip25_entry;
+pub mod committee_authorization;
+pub mod dex_candle;
+pub mod dex_liquidity;
 pub mod dex_swap;
+pub mod drep_registration;
+pub mod drep_stake_snapshot;
+pub mod execution_plan;
+pub mod governance_proposals;
 pub mod governance_votes;
 pub mod native_asset;
 pub mod plutus_data;
 pub mod plutus_data_hash;
+pub mod pool_registration;
+pub mod pool_stake_snapshot;
 pub mod projected_nft;
+pub mod redeemer;
+pub mod reference_script;
+pub mod script;
 // todo: rename to pool?
 pub mod stake_delegation;
 pub mod stake_delegation_drep;
+pub mod task_block_range;
 pub mod transaction_metadata;
+pub mod unspent_output;
+pub mod withdrawal;
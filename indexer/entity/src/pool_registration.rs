@@ -0,0 +1,53 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// History of the registrations, updates and retirements of stake pools
+/// Registration fields are null for retirements and `retiring_epoch` is null for registrations
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "PoolRegistration")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    /// pool key hash (same format as `StakeDelegationCredentialRelation::pool_credential`)
+    pub pool_credential: Vec<u8>,
+    #[sea_orm(column_type = "BigInteger")]
+    pub tx_id: i64,
+    pub vrf_key_hash: Option<Vec<u8>>,
+    #[sea_orm(column_type = "BigUnsigned", nullable)]
+    pub pledge: Option<u64>,
+    #[sea_orm(column_type = "BigUnsigned", nullable)]
+    pub cost: Option<u64>,
+    #[sea_orm(column_type = "BigUnsigned", nullable)]
+    pub margin_numerator: Option<u64>,
+    #[sea_orm(column_type = "BigUnsigned", nullable)]
+    pub margin_denominator: Option<u64>,
+    pub reward_address: Option<Vec<u8>>,
+    pub metadata_url: Option<String>,
+    pub metadata_hash: Option<Vec<u8>>,
+    /// cbor of the set of owner key hashes
+    pub owners: Option<Vec<u8>>,
+    /// cbor array of the relays, in the order of the certificate
+    pub relays: Option<Vec<u8>>,
+    /// cbor of the full pool parameters
+    pub pool_params: Option<Vec<u8>>,
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub retiring_epoch: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::TxId",
+        to = "super::transaction::Column::Id"
+    )]
+    Transaction,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Stake delegated to each stake pool at the end of an epoch
+/// Reward balances are ledger state that doesn't appear on-chain, so they are not part of the stake
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "PoolStakeSnapshot")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    /// first block of the next epoch, in which the snapshot was taken
+    pub block_id: i32,
+    pub epoch: i32,
+    /// pool key hash (same format as `StakeDelegationCredentialRelation::pool_credential`)
+    pub pool_credential: Vec<u8>,
+    /// lovelace in the unspent outputs of the delegators
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub amount: u64,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub delegator_count: u64,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::block::Entity",
+        from = "Column::BlockId",
+        to = "super::block::Column::Id"
+    )]
+    Block,
+}
+
+impl Related<super::block::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Block.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
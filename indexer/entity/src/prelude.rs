@@ -2,6 +2,11 @@ pub use super::address::{
     ActiveModel as AddressActiveModel, Column as AddressColumn, Entity as Address,
     Model as AddressModel, PrimaryKey as AddressPrimaryKey, Relation as AddressRelation,
 };
+pub use super::address_balance::{
+    ActiveModel as AddressBalanceActiveModel, Column as AddressBalanceColumn,
+    Entity as AddressBalance, Model as AddressBalanceModel, PrimaryKey as AddressBalancePrimaryKey,
+    Relation as AddressBalanceRelation,
+};
 pub use super::address_credential::{
     ActiveModel as AddressCredentialActiveModel, Column as AddressCredentialColumn,
     Entity as AddressCredential, Model as AddressCredentialModel,
@@ -23,10 +28,44 @@ pub use super::cip25_entry::{
     ActiveModel as Cip25EntryActiveModel, Column as Cip25EntryColumn, Entity as Cip25Entry,
     Model as Cip25EntryModel, PrimaryKey as Cip25EntryPrimaryKey, Relation as Cip25EntryRelation,
 };
+pub use super::committee_authorization::{
+    ActiveModel as CommitteeAuthorizationActiveModel, Column as CommitteeAuthorizationColumn,
+    Entity as CommitteeAuthorization, Model as CommitteeAuthorizationModel,
+    PrimaryKey as CommitteeAuthorizationPrimaryKey, Relation as CommitteeAuthorizationRelation,
+};
+pub use super::dex_candle::{
+    ActiveModel as DexCandleActiveModel, Column as DexCandleColumn, Entity as DexCandle,
+    Model as DexCandleModel, PrimaryKey as DexCandlePrimaryKey, Relation as DexCandleRelation,
+};
+pub use super::dex_liquidity::{
+    ActiveModel as DexLiquidityActiveModel, Column as DexLiquidityColumn, Entity as DexLiquidity,
+    Model as DexLiquidityModel, PrimaryKey as DexLiquidityPrimaryKey,
+    Relation as DexLiquidityRelation,
+};
 pub use super::dex_swap::{
     ActiveModel as DexSwapActiveModel, Column as DexSwapColumn, Entity as DexSwap,
     Model as DexSwapModel, PrimaryKey as DexSwapPrimaryKey, Relation as DexSwapRelation,
 };
+pub use super::drep_registration::{
+    ActiveModel as DrepRegistrationActiveModel, Column as DrepRegistrationColumn,
+    Entity as DrepRegistration, Model as DrepRegistrationModel,
+    PrimaryKey as DrepRegistrationPrimaryKey, Relation as DrepRegistrationRelation,
+};
+pub use super::drep_stake_snapshot::{
+    ActiveModel as DrepStakeSnapshotActiveModel, Column as DrepStakeSnapshotColumn,
+    Entity as DrepStakeSnapshot, Model as DrepStakeSnapshotModel,
+    PrimaryKey as DrepStakeSnapshotPrimaryKey, Relation as DrepStakeSnapshotRelation,
+};
+pub use super::execution_plan::{
+    ActiveModel as ExecutionPlanActiveModel, Column as ExecutionPlanColumn,
+    Entity as ExecutionPlan, Model as ExecutionPlanModel, PrimaryKey as ExecutionPlanPrimaryKey,
+    Relation as ExecutionPlanRelation,
+};
+pub use super::governance_proposals::{
+    ActiveModel as GovernanceProposalActiveModel, Column as GovernanceProposalColumn,
+    Entity as GovernanceProposal, Model as GovernanceProposalModel,
+    PrimaryKey as GovernanceProposalPrimaryKey, Relation as GovernanceProposalRelation,
+};
 pub use super::governance_votes::{
     ActiveModel as GovernanceVoteActiveModel, Column as GovernanceVoteColumn,
     Entity as GovernanceVote, Model as GovernanceVoteModel, PrimaryKey as GovernanceVotePrimaryKey,
@@ -46,16 +85,49 @@ pub use super::plutus_data_hash::{
     Entity as PlutusDataHash, Model as PlutusDataHashModel, PrimaryKey as PlutusDataHashPrimaryKey,
     Relation as PlutusDataHashRelation,
 };
+pub use super::pool_registration::{
+    ActiveModel as PoolRegistrationActiveModel, Column as PoolRegistrationColumn,
+    Entity as PoolRegistration, Model as PoolRegistrationModel,
+    PrimaryKey as PoolRegistrationPrimaryKey, Relation as PoolRegistrationRelation,
+};
+pub use super::pool_stake_snapshot::{
+    ActiveModel as PoolStakeSnapshotActiveModel, Column as PoolStakeSnapshotColumn,
+    Entity as PoolStakeSnapshot, Model as PoolStakeSnapshotModel,
+    PrimaryKey as PoolStakeSnapshotPrimaryKey, Relation as PoolStakeSnapshotRelation,
+};
 pub use super::projected_nft::{
     ActiveModel as ProjectedNftActiveModel, Column as ProjectedNftColumn, Entity as ProjectedNft,
     Model as ProjectedNftModel, PrimaryKey as ProjectedNftPrimaryKey,
     Relation as ProjectedNftRelation,
 };
+pub use super::redeemer::{
+    ActiveModel as RedeemerActiveModel, Column as RedeemerColumn, Entity as Redeemer,
+    Model as RedeemerModel, PrimaryKey as RedeemerPrimaryKey, Relation as RedeemerRelation,
+};
+pub use super::reference_script::{
+    ActiveModel as ReferenceScriptActiveModel, Column as ReferenceScriptColumn,
+    Entity as ReferenceScript, Model as ReferenceScriptModel,
+    PrimaryKey as ReferenceScriptPrimaryKey, Relation as ReferenceScriptRelation,
+};
+pub use super::script::{
+    ActiveModel as ScriptActiveModel, Column as ScriptColumn, Entity as Script,
+    Model as ScriptModel, PrimaryKey as ScriptPrimaryKey, Relation as ScriptRelation,
+};
 pub use super::stake_credential::{
     ActiveModel as StakeCredentialActiveModel, Column as StakeCredentialColumn,
     Entity as StakeCredential, Model as StakeCredentialModel,
     PrimaryKey as StakeCredentialPrimaryKey, Relation as StakeCredentialRelation,
 };
+pub use super::stake_credential_balance::{
+    ActiveModel as StakeCredentialBalanceActiveModel, Column as StakeCredentialBalanceColumn,
+    Entity as StakeCredentialBalance, Model as StakeCredentialBalanceModel,
+    PrimaryKey as StakeCredentialBalancePrimaryKey, Relation as StakeCredentialBalanceRelation,
+};
+pub use super::task_block_range::{
+    ActiveModel as TaskBlockRangeActiveModel, Column as TaskBlockRangeColumn,
+    Entity as TaskBlockRange, Model as TaskBlockRangeModel, PrimaryKey as TaskBlockRangePrimaryKey,
+    Relation as TaskBlockRangeRelation,
+};
 pub use super::transaction::{
     ActiveModel as TransactionActiveModel, Column as TransactionColumn, Entity as Transaction,
     Model as TransactionModel, PrimaryKey as TransactionPrimaryKey,
@@ -87,3 +159,12 @@ pub use super::tx_credential::{
     Model as TxCredentialModel, PrimaryKey as TxCredentialPrimaryKey,
     Relation as TxCredentialRelation,
 };
+pub use super::unspent_output::{
+    ActiveModel as UnspentOutputActiveModel, Column as UnspentOutputColumn,
+    Entity as UnspentOutput, Model as UnspentOutputModel, PrimaryKey as UnspentOutputPrimaryKey,
+    Relation as UnspentOutputRelation,
+};
+pub use super::withdrawal::{
+    ActiveModel as WithdrawalActiveModel, Column as WithdrawalColumn, Entity as Withdrawal,
+    Model as WithdrawalModel, PrimaryKey as WithdrawalPrimaryKey, Relation as WithdrawalRelation,
+};
@@ -0,0 +1,76 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "Redeemer")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    #[sea_orm(column_type = "BigInteger")]
+    pub tx_id: i64,
+    /// see `Purpose`
+    pub tag: i32,
+    /// index of the target in the (sorted) inputs, policies, certificates, withdrawals, voters or proposals
+    #[sea_orm(column_type = "BigInteger")]
+    pub index: i64,
+    /// spent output (only for spend redeemers of valid transactions)
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub utxo_id: Option<i64>,
+    /// policy id (mint), cbor of the credential (cert, reward), cbor of the voter (vote)
+    /// or cbor of the gov action id (propose)
+    pub target: Option<Vec<u8>>,
+    /// cbor of the PlutusData passed to the script
+    pub data: Vec<u8>,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub ex_units_mem: u64,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub ex_units_steps: u64,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::TxId",
+        to = "super::transaction::Column::Id"
+    )]
+    Transaction,
+    #[sea_orm(
+        belongs_to = "super::transaction_output::Entity",
+        from = "Column::UtxoId",
+        to = "super::transaction_output::Column::Id"
+    )]
+    TransactionOutput,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Values follow the redeemer tags of the CDDL
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Purpose {
+    Spend,
+    Mint,
+    Cert,
+    Reward,
+    Vote,
+    Propose,
+}
+
+impl From<Purpose> for i32 {
+    fn from(item: Purpose) -> Self {
+        match item {
+            Purpose::Spend => 0,
+            Purpose::Mint => 1,
+            Purpose::Cert => 2,
+            Purpose::Reward => 3,
+            Purpose::Vote => 4,
+            Purpose::Propose => 5,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Outputs that carry a reference script
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "ReferenceScript")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    #[sea_orm(column_type = "BigInteger", unique)]
+    pub output_id: i64,
+    #[sea_orm(column_type = "BigInteger")]
+    pub script_id: i64,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction_output::Entity",
+        from = "Column::OutputId",
+        to = "super::transaction_output::Column::Id"
+    )]
+    TransactionOutput,
+    #[sea_orm(
+        belongs_to = "super::script::Entity",
+        from = "Column::ScriptId",
+        to = "super::script::Column::Id"
+    )]
+    Script,
+}
+
+impl Related<super::transaction_output::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionOutput.def()
+    }
+}
+
+impl Related<super::script::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Script.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,65 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "Script")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub hash: Vec<u8>,
+    /// see `ScriptType`
+    pub script_type: i32,
+    /// size in bytes of `cbor`
+    pub size: i32,
+    /// cbor of the native script, or the (cbor-wrapped) flat encoding of the plutus script
+    pub cbor: Vec<u8>,
+    #[sea_orm(column_type = "BigInteger")]
+    pub first_tx: i64,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::FirstTx",
+        to = "super::transaction::Column::Id"
+    )]
+    Transaction,
+    #[sea_orm(has_many = "super::reference_script::Entity")]
+    ReferenceScript,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl Related<super::reference_script::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReferenceScript.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Values follow the script tags of the CDDL
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScriptType {
+    Native,
+    PlutusV1,
+    PlutusV2,
+    PlutusV3,
+}
+
+impl From<ScriptType> for i32 {
+    fn from(item: ScriptType) -> Self {
+        match item {
+            ScriptType::Native => 0,
+            ScriptType::PlutusV1 => 1,
+            ScriptType::PlutusV2 => 2,
+            ScriptType::PlutusV3 => 3,
+        }
+    }
+}
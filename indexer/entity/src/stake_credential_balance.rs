@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Amount of an asset held by a stake credential (summed over the base addresses using it) in unspent outputs
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "StakeCredentialBalance")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    #[sea_orm(column_type = "BigInteger")]
+    pub credential_id: i64,
+    /// null for ADA
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub asset_id: Option<i64>,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub amount: u64,
+    /// last block that changed the balance
+    #[sea_orm(nullable)]
+    pub last_block_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::stake_credential::Entity",
+        from = "Column::CredentialId",
+        to = "super::stake_credential::Column::Id"
+    )]
+    StakeCredential,
+    #[sea_orm(
+        belongs_to = "super::native_asset::Entity",
+        from = "Column::AssetId",
+        to = "super::native_asset::Column::Id"
+    )]
+    NativeAsset,
+    #[sea_orm(
+        belongs_to = "super::block::Entity",
+        from = "Column::LastBlockId",
+        to = "super::block::Column::Id"
+    )]
+    Block,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Range of blocks each task was run on, with the configuration and version it had
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "TaskBlockRange")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub task_name: String,
+    /// hash of the toml configuration of the task in the execution plan
+    pub config_hash: Vec<u8>,
+    /// version of the task (see `DatabaseTaskMeta::VERSION`)
+    pub version: i32,
+    /// height of the first block processed by the task with this configuration and version
+    pub start_height: i32,
+    /// height of the last block processed by the task with this configuration and version (None if no block was)
+    pub end_height: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,57 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Outputs that are unspent, or were spent recently enough to be restored by a rollback
+/// The live UTxO set is the rows where `spent_tx_id` is null
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "UnspentOutput")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    #[sea_orm(column_type = "BigInteger", unique)]
+    pub output_id: i64,
+    #[sea_orm(column_type = "BigInteger")]
+    pub address_id: i64,
+    /// stake credential of the base address (null for other address types)
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub stake_credential_id: Option<i64>,
+    /// reset to null when the spending transaction is rolled back
+    #[sea_orm(column_type = "BigInteger", nullable)]
+    pub spent_tx_id: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction_output::Entity",
+        from = "Column::OutputId",
+        to = "super::transaction_output::Column::Id"
+    )]
+    TransactionOutput,
+    #[sea_orm(
+        belongs_to = "super::address::Entity",
+        from = "Column::AddressId",
+        to = "super::address::Column::Id"
+    )]
+    Address,
+    #[sea_orm(
+        belongs_to = "super::stake_credential::Entity",
+        from = "Column::StakeCredentialId",
+        to = "super::stake_credential::Column::Id"
+    )]
+    StakeCredential,
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::SpentTxId",
+        to = "super::transaction::Column::Id"
+    )]
+    SpentTransaction,
+}
+
+impl Related<super::transaction_output::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionOutput.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,49 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Rewards withdrawn by transactions
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "Withdrawal")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = "BigInteger")]
+    pub id: i64,
+    #[sea_orm(column_type = "BigInteger")]
+    pub tx_id: i64,
+    /// reward address the rewards are withdrawn from
+    #[sea_orm(column_type = "BigInteger")]
+    pub address_id: i64,
+    #[sea_orm(column_type = "BigInteger")]
+    pub stake_credential_id: i64,
+    #[sea_orm(column_type = "BigUnsigned")]
+    pub amount: u64,
+}
+
+#[derive(Copy, Clone, Debug, DeriveRelation, EnumIter)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::TxId",
+        to = "super::transaction::Column::Id"
+    )]
+    Transaction,
+    #[sea_orm(
+        belongs_to = "super::address::Entity",
+        from = "Column::AddressId",
+        to = "super::address::Column::Id"
+    )]
+    Address,
+    #[sea_orm(
+        belongs_to = "super::stake_credential::Entity",
+        from = "Column::StakeCredentialId",
+        to = "super::stake_credential::Column::Id"
+    )]
+    StakeCredential,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
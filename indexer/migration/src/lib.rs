@@ -23,6 +23,23 @@ mod m20231220_000018_asset_utxo_table;
 mod m20240229_000019_add_block_tx_count_column;
 mod m20240326_000020_create_drep_delegation_table;
 mod m20240326_000021_create_governance_voting_table;
+mod m20240415_000022_create_execution_plan_table;
+mod m20240415_000023_create_task_block_range_table;
+mod m20240503_000024_create_dex_liquidity_table;
+mod m20240510_000025_create_dex_candle_table;
+mod m20240517_000026_create_pool_registration_table;
+mod m20240524_000027_create_governance_proposal_table;
+mod m20240531_000028_create_drep_registration_table;
+mod m20240607_000029_create_committee_authorization_table;
+mod m20240614_000030_create_pool_stake_snapshot_table;
+mod m20240614_000031_create_drep_stake_snapshot_table;
+mod m20240621_000032_create_address_balance_table;
+mod m20240621_000033_create_stake_credential_balance_table;
+mod m20240628_000034_create_unspent_output_table;
+mod m20240705_000035_create_withdrawal_table;
+mod m20240712_000036_create_redeemer_table;
+mod m20240719_000037_create_script_table;
+mod m20240719_000038_create_reference_script_table;
 
 pub struct Migrator;
 
@@ -53,6 +70,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20240229_000019_add_block_tx_count_column::Migration),
             Box::new(m20240326_000020_create_drep_delegation_table::Migration),
             Box::new(m20240326_000021_create_governance_voting_table::Migration),
+            Box::new(m20240415_000022_create_execution_plan_table::Migration),
+            Box::new(m20240415_000023_create_task_block_range_table::Migration),
+            Box::new(m20240503_000024_create_dex_liquidity_table::Migration),
+            Box::new(m20240510_000025_create_dex_candle_table::Migration),
+            Box::new(m20240517_000026_create_pool_registration_table::Migration),
+            Box::new(m20240524_000027_create_governance_proposal_table::Migration),
+            Box::new(m20240531_000028_create_drep_registration_table::Migration),
+            Box::new(m20240607_000029_create_committee_authorization_table::Migration),
+            Box::new(m20240614_000030_create_pool_stake_snapshot_table::Migration),
+            Box::new(m20240614_000031_create_drep_stake_snapshot_table::Migration),
+            Box::new(m20240621_000032_create_address_balance_table::Migration),
+            Box::new(m20240621_000033_create_stake_credential_balance_table::Migration),
+            Box::new(m20240628_000034_create_unspent_output_table::Migration),
+            Box::new(m20240705_000035_create_withdrawal_table::Migration),
+            Box::new(m20240712_000036_create_redeemer_table::Migration),
+            Box::new(m20240719_000037_create_script_table::Migration),
+            Box::new(m20240719_000038_create_reference_script_table::Migration),
         ]
     }
 }
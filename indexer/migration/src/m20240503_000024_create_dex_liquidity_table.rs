@@ -0,0 +1,122 @@
+use sea_schema::migration::prelude::*;
+
+use entity::dex_liquidity::*;
+use entity::prelude::{
+    Address, AddressColumn, NativeAsset, NativeAssetColumn, Transaction, TransactionColumn,
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240503_000024_create_dex_liquidity_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::TxId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_liquidity-tx_id")
+                            .from(Entity, Column::TxId)
+                            .to(Transaction, TransactionColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::AddressId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_liquidity-address_id")
+                            .from(Entity, Column::AddressId)
+                            .to(Address, AddressColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Column::OwnerAddressId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_liquidity-owner_address_id")
+                            .from(Entity, Column::OwnerAddressId)
+                            .to(Address, AddressColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Dex).big_integer().not_null())
+                    .col(ColumnDef::new(Column::Asset1Id).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_liquidity-asset1_id")
+                            .from(Entity, Column::Asset1Id)
+                            .to(NativeAsset, NativeAssetColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Asset2Id).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_liquidity-asset2_id")
+                            .from(Entity, Column::Asset2Id)
+                            .to(NativeAsset, NativeAssetColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Amount1).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::Amount2).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::LpAssetId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_liquidity-lp_asset_id")
+                            .from(Entity, Column::LpAssetId)
+                            .to(NativeAsset, NativeAssetColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::LpAmount).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::Operation).big_integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-dex_liquidity-native_asset1-native_asset2-transaction")
+                    .col(Column::Dex)
+                    .col(Column::Asset1Id)
+                    .col(Column::Asset2Id)
+                    .col(Column::TxId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-dex_liquidity-owner_address-lp_asset")
+                    .col(Column::OwnerAddressId)
+                    .col(Column::LpAssetId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
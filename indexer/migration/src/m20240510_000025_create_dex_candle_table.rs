@@ -0,0 +1,98 @@
+use sea_schema::migration::prelude::*;
+
+use entity::dex_candle::*;
+use entity::prelude::{Block, BlockColumn, NativeAsset, NativeAssetColumn};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240510_000025_create_dex_candle_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::Dex).big_integer().not_null())
+                    .col(ColumnDef::new(Column::Asset1Id).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_candle-asset1_id")
+                            .from(Entity, Column::Asset1Id)
+                            .to(NativeAsset, NativeAssetColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Asset2Id).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_candle-asset2_id")
+                            .from(Entity, Column::Asset2Id)
+                            .to(NativeAsset, NativeAssetColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Interval).big_integer().not_null())
+                    .col(ColumnDef::new(Column::BucketStart).big_integer().not_null())
+                    .col(ColumnDef::new(Column::Open).double().not_null())
+                    .col(ColumnDef::new(Column::High).double().not_null())
+                    .col(ColumnDef::new(Column::Low).double().not_null())
+                    .col(ColumnDef::new(Column::Close).double().not_null())
+                    .col(ColumnDef::new(Column::Volume1).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::Volume2).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::LastBlockId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-dex_candle-last_block_id")
+                            .from(Entity, Column::LastBlockId)
+                            .to(Block, BlockColumn::Id)
+                            // candles also contain swaps of older blocks
+                            // so they are recomputed instead of being deleted
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-dex_candle-native_asset1-native_asset2-interval-bucket_start")
+                    .col(Column::Dex)
+                    .col(Column::Asset1Id)
+                    .col(Column::Asset2Id)
+                    .col(Column::Interval)
+                    .col(Column::BucketStart)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-dex_candle-last_block_id")
+                    .col(Column::LastBlockId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,69 @@
+use entity::pool_registration::*;
+use entity::prelude::{Transaction, TransactionColumn};
+use sea_schema::migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240517_000026_create_pool_registration_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::PoolCredential).binary().not_null())
+                    .col(ColumnDef::new(Column::TxId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-pool_registration-tx_id")
+                            .from(Entity, Column::TxId)
+                            .to(Transaction, TransactionColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::VrfKeyHash).binary())
+                    .col(ColumnDef::new(Column::Pledge).big_unsigned())
+                    .col(ColumnDef::new(Column::Cost).big_unsigned())
+                    .col(ColumnDef::new(Column::MarginNumerator).big_unsigned())
+                    .col(ColumnDef::new(Column::MarginDenominator).big_unsigned())
+                    .col(ColumnDef::new(Column::RewardAddress).binary())
+                    .col(ColumnDef::new(Column::MetadataUrl).text())
+                    .col(ColumnDef::new(Column::MetadataHash).binary())
+                    .col(ColumnDef::new(Column::Owners).binary())
+                    .col(ColumnDef::new(Column::Relays).binary())
+                    .col(ColumnDef::new(Column::PoolParams).binary())
+                    .col(ColumnDef::new(Column::RetiringEpoch).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-pool_registration-pool_credential")
+                    .col(Column::PoolCredential)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
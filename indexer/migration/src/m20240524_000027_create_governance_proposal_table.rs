@@ -0,0 +1,98 @@
+use entity::governance_proposals::*;
+use entity::prelude::{Transaction, TransactionColumn};
+use sea_schema::migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240524_000027_create_governance_proposal_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::TxId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-governance_proposal-tx_id")
+                            .from(Entity, Column::TxId)
+                            .to(Transaction, TransactionColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Index).big_integer().not_null())
+                    .col(ColumnDef::new(Column::GovActionId).binary().not_null())
+                    .col(ColumnDef::new(Column::ActionTxHash).binary().not_null())
+                    .col(ColumnDef::new(Column::ActionType).integer().not_null())
+                    .col(ColumnDef::new(Column::Deposit).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::RewardAddress).binary().not_null())
+                    .col(ColumnDef::new(Column::AnchorUrl).text().not_null())
+                    .col(ColumnDef::new(Column::AnchorHash).binary().not_null())
+                    .col(ColumnDef::new(Column::GovAction).binary().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-governance_proposal-tx_id-index")
+                    .col(Column::TxId)
+                    .col(Column::Index)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-governance_proposal-gov_action_id")
+                    .col(Column::GovActionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-governance_proposal-action_tx_hash-index")
+                    .col(Column::ActionTxHash)
+                    .col(Column::Index)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-governance_proposal-action_type")
+                    .col(Column::ActionType)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
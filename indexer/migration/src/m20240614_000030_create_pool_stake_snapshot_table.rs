@@ -0,0 +1,76 @@
+use entity::pool_stake_snapshot::*;
+use entity::prelude::{Block, BlockColumn};
+use sea_schema::migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240614_000030_create_pool_stake_snapshot_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::BlockId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-pool_stake_snapshot-block_id")
+                            .from(Entity, Column::BlockId)
+                            .to(Block, BlockColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Epoch).integer().not_null())
+                    .col(ColumnDef::new(Column::PoolCredential).binary().not_null())
+                    .col(ColumnDef::new(Column::Amount).big_unsigned().not_null())
+                    .col(
+                        ColumnDef::new(Column::DelegatorCount)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-pool_stake_snapshot-epoch-pool_credential")
+                    .col(Column::Epoch)
+                    .col(Column::PoolCredential)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-pool_stake_snapshot-block_id")
+                    .col(Column::BlockId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
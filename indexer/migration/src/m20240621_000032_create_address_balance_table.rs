@@ -0,0 +1,85 @@
+use sea_schema::migration::prelude::*;
+
+use entity::address_balance::*;
+use entity::prelude::{Address, AddressColumn, Block, BlockColumn, NativeAsset, NativeAssetColumn};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240621_000032_create_address_balance_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::AddressId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-address_balance-address_id")
+                            .from(Entity, Column::AddressId)
+                            .to(Address, AddressColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::AssetId).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-address_balance-asset_id")
+                            .from(Entity, Column::AssetId)
+                            .to(NativeAsset, NativeAssetColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Amount).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::LastBlockId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-address_balance-last_block_id")
+                            .from(Entity, Column::LastBlockId)
+                            .to(Block, BlockColumn::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-address_balance-address_id-asset_id")
+                    .col(Column::AddressId)
+                    .col(Column::AssetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-address_balance-last_block_id")
+                    .col(Column::LastBlockId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
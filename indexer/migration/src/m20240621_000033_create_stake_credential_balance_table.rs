@@ -0,0 +1,91 @@
+use sea_schema::migration::prelude::*;
+
+use entity::prelude::{
+    Block, BlockColumn, NativeAsset, NativeAssetColumn, StakeCredential, StakeCredentialColumn,
+};
+use entity::stake_credential_balance::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240621_000033_create_stake_credential_balance_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Column::CredentialId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-stake_credential_balance-credential_id")
+                            .from(Entity, Column::CredentialId)
+                            .to(StakeCredential, StakeCredentialColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::AssetId).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-stake_credential_balance-asset_id")
+                            .from(Entity, Column::AssetId)
+                            .to(NativeAsset, NativeAssetColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Amount).big_unsigned().not_null())
+                    .col(ColumnDef::new(Column::LastBlockId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-stake_credential_balance-last_block_id")
+                            .from(Entity, Column::LastBlockId)
+                            .to(Block, BlockColumn::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-stake_credential_balance-credential_id-asset_id")
+                    .col(Column::CredentialId)
+                    .col(Column::AssetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-stake_credential_balance-last_block_id")
+                    .col(Column::LastBlockId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
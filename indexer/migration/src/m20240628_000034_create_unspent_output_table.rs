@@ -0,0 +1,112 @@
+use sea_schema::migration::prelude::*;
+
+use entity::prelude::{
+    Address, AddressColumn, StakeCredential, StakeCredentialColumn, Transaction, TransactionColumn,
+    TransactionOutput, TransactionOutputColumn,
+};
+use entity::unspent_output::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240628_000034_create_unspent_output_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Column::OutputId)
+                            .big_integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-unspent_output-output_id")
+                            .from(Entity, Column::OutputId)
+                            .to(TransactionOutput, TransactionOutputColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::AddressId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-unspent_output-address_id")
+                            .from(Entity, Column::AddressId)
+                            .to(Address, AddressColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::StakeCredentialId).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-unspent_output-stake_credential_id")
+                            .from(Entity, Column::StakeCredentialId)
+                            .to(StakeCredential, StakeCredentialColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::SpentTxId).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-unspent_output-spent_tx_id")
+                            .from(Entity, Column::SpentTxId)
+                            .to(Transaction, TransactionColumn::Id)
+                            // rolling back the spending transaction makes the output unspent again
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-unspent_output-address_id-spent_tx_id")
+                    .col(Column::AddressId)
+                    .col(Column::SpentTxId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-unspent_output-stake_credential_id-spent_tx_id")
+                    .col(Column::StakeCredentialId)
+                    .col(Column::SpentTxId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-unspent_output-spent_tx_id")
+                    .col(Column::SpentTxId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
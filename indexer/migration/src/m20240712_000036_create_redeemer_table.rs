@@ -0,0 +1,95 @@
+use sea_schema::migration::prelude::*;
+
+use entity::prelude::{Transaction, TransactionColumn, TransactionOutput, TransactionOutputColumn};
+use entity::redeemer::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20240712_000036_create_redeemer_table"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::TxId).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-redeemer-tx_id")
+                            .from(Entity, Column::TxId)
+                            .to(Transaction, TransactionColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Tag).integer().not_null())
+                    .col(ColumnDef::new(Column::Index).big_integer().not_null())
+                    .col(ColumnDef::new(Column::UtxoId).big_integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-redeemer-utxo_id")
+                            .from(Entity, Column::UtxoId)
+                            .to(TransactionOutput, TransactionOutputColumn::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Column::Target).binary())
+                    .col(ColumnDef::new(Column::Data).binary().not_null())
+                    .col(ColumnDef::new(Column::ExUnitsMem).big_unsigned().not_null())
+                    .col(
+                        ColumnDef::new(Column::ExUnitsSteps)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-redeemer-tx_id")
+                    .col(Column::TxId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-redeemer-utxo_id")
+                    .col(Column::UtxoId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Entity)
+                    .name("index-redeemer-target")
+                    .col(Column::Target)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
@@ -42,32 +42,44 @@ pub fn generate(exec_plan: &ExecutionPlan, plan_name: &str) -> Graph {
                 None => {
                     panic!("Could not find task named {task_name}");
                 }
-                Some(task) => match task {
-                    TaskRegistryEntry::Genesis(entry) => {
-                        add_node(
-                            SubgraphNames::Genesis,
-                            task_name,
-                            entry.builder.get_name(),
-                            entry.builder.get_dependencies(),
-                        );
+                Some(task) => {
+                    let dependencies: Vec<&str> = task
+                        .get_dependencies()
+                        .iter()
+                        .chain(
+                            task.get_optional_dependencies()
+                                .iter()
+                                .filter(|dependency| exec_plan.0.contains_key(**dependency)),
+                        )
+                        .copied()
+                        .collect();
+                    match task {
+                        TaskRegistryEntry::Genesis(entry) => {
+                            add_node(
+                                SubgraphNames::Genesis,
+                                task_name,
+                                entry.builder.get_name(),
+                                &dependencies,
+                            );
+                        }
+                        TaskRegistryEntry::Byron(entry) => {
+                            add_node(
+                                SubgraphNames::Byron,
+                                task_name,
+                                entry.builder.get_name(),
+                                &dependencies,
+                            );
+                        }
+                        TaskRegistryEntry::Multiera(entry) => {
+                            add_node(
+                                SubgraphNames::Multiera,
+                                task_name,
+                                entry.builder.get_name(),
+                                &dependencies,
+                            );
+                        }
                     }
-                    TaskRegistryEntry::Byron(entry) => {
-                        add_node(
-                            SubgraphNames::Byron,
-                            task_name,
-                            entry.builder.get_name(),
-                            entry.builder.get_dependencies(),
-                        );
-                    }
-                    TaskRegistryEntry::Multiera(entry) => {
-                        add_node(
-                            SubgraphNames::Multiera,
-                            task_name,
-                            entry.builder.get_name(),
-                            entry.builder.get_dependencies(),
-                        );
-                    }
-                },
+                }
             }
         }
     }
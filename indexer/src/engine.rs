@@ -1,12 +1,15 @@
+use crate::metrics::Metrics;
 use crate::perf_aggregator::PerfAggregator;
 use crate::sink::Sink;
 use crate::types::StoppableService;
+use anyhow::anyhow;
 use async_trait::async_trait;
 use dcspark_blockchain_source::{GetNextFrom, PullFrom, Source};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::SeqCst;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::mpsc;
 
 pub struct FetchEngine<
     FromType: PullFrom + Clone,
@@ -14,15 +17,18 @@ pub struct FetchEngine<
     SourceType: Source<From = FromType, Event = EventType> + StoppableService + Send,
     SinkType: Sink<From = FromType, Event = EventType> + StoppableService + Send,
 > {
-    source: SourceType,
+    // note: the source is moved to a separate tokio task while blocks are being processed
+    source: Option<SourceType>,
     sink: SinkType,
     running: Arc<AtomicBool>,
+    metrics: Metrics,
+    prefetch_depth: usize,
 }
 
 impl<
-        FromType: PullFrom + Clone,
-        EventType: std::fmt::Debug + GetNextFrom<From = FromType>,
-        SourceType: Source<From = FromType, Event = EventType> + StoppableService + Send,
+        FromType: PullFrom + Clone + Send + 'static,
+        EventType: std::fmt::Debug + GetNextFrom<From = FromType> + Send + 'static,
+        SourceType: Source<From = FromType, Event = EventType> + StoppableService + Send + 'static,
         SinkType: Sink<From = FromType, Event = EventType> + StoppableService + Send,
     > FetchEngine<FromType, EventType, SourceType, SinkType>
 {
@@ -30,39 +36,134 @@ impl<
         source: SourceType,
         sink: SinkType,
         running: Arc<AtomicBool>,
+        metrics: Metrics,
+        prefetch_depth: usize,
     ) -> FetchEngine<FromType, EventType, SourceType, SinkType> {
         Self {
-            source,
+            source: Some(source),
             sink,
             running,
+            metrics,
+            // a channel can't have a capacity of 0
+            prefetch_depth: std::cmp::max(prefetch_depth, 1),
         }
     }
 
+    /// Pulls events from the source in a separate task so that fetching the next blocks overlaps
+    /// with the sink processing the current one.
+    /// Events are queued in a bounded FIFO channel, so:
+    /// 1) the source is paused when `prefetch_depth` events are waiting to be processed
+    /// 2) rollbacks are processed in the same order they were received, relative to blocks
     pub async fn fetch_and_process(&mut self, from: FromType) -> anyhow::Result<()> {
         tracing::info!("{}", "Starting to process blocks");
-        let mut pull_from = from;
+
+        let mut source = self
+            .source
+            .take()
+            .ok_or_else(|| anyhow!("Source is already being used"))?;
+        let (sender, mut receiver) = mpsc::channel::<EventType>(self.prefetch_depth);
+        let running = self.running.clone();
+        let metrics = self.metrics.clone();
+        let fetcher = tokio::spawn(async move {
+            let result = fetch(&mut source, from, sender, running, metrics).await;
+            (source, result)
+        });
 
         let mut perf_aggregator = PerfAggregator::new();
+        let mut process_result = Ok(());
 
         while self.running.load(SeqCst) {
-            let event_fetch_start = std::time::Instant::now();
-            let event = self.source.pull(&pull_from).await?;
-            let event = if let Some(event) = event {
-                event
-            } else {
-                tokio::time::sleep(Duration::from_millis(200)).await;
-                continue;
+            // note: only the time spent waiting for the queue counts as fetch time
+            // since the rest of the fetching happens in parallel to the processing
+            let event_wait_start = std::time::Instant::now();
+            let event = match self.sink.flush_deadline() {
+                Some(deadline) => tokio::select! {
+                    event = receiver.recv() => event,
+                    _ = tokio::time::sleep_until(deadline.into()) => {
+                        if let Err(error) = self.sink.flush().await {
+                            process_result = Err(error);
+                            break;
+                        }
+                        continue;
+                    }
+                },
+                None => receiver.recv().await,
+            };
+            let event = match event {
+                Some(event) => event,
+                None => break,
             };
-            perf_aggregator.block_fetch += event_fetch_start.elapsed();
-            let new_from = event.next_from().unwrap_or(pull_from);
-            self.sink.process(event, &mut perf_aggregator).await?;
-            pull_from = new_from;
+            perf_aggregator.block_fetch += event_wait_start.elapsed();
+
+            if let Err(error) = self.sink.process(event, &mut perf_aggregator).await {
+                process_result = Err(error);
+                break;
+            }
         }
 
-        Ok(())
+        // dropping the receiver stops the fetcher, even while it waits for the source
+        drop(receiver);
+        let (source, fetch_result) = fetcher.await?;
+        self.source = Some(source);
+
+        process_result.and(fetch_result)
+    }
+
+    /// Stops the source but keeps the sink running so that a new source can be plugged to it
+    /// (ex: to recover from a fork)
+    pub async fn stop_source(self) -> SinkType {
+        if let Some(source) = self.source {
+            let _ = source.stop().await.map_err(|err| {
+                tracing::error!("Error during source shutdown: {:?}", err);
+            });
+        }
+        self.sink
     }
 }
 
+async fn fetch<FromType, EventType, SourceType>(
+    source: &mut SourceType,
+    from: FromType,
+    sender: mpsc::Sender<EventType>,
+    running: Arc<AtomicBool>,
+    metrics: Metrics,
+) -> anyhow::Result<()>
+where
+    FromType: PullFrom + Clone,
+    EventType: GetNextFrom<From = FromType>,
+    SourceType: Source<From = FromType, Event = EventType>,
+{
+    let mut pull_from = from;
+
+    while running.load(SeqCst) {
+        let event_fetch_start = std::time::Instant::now();
+        // the processing loop stopping (ex: on an error or a fork) cancels the pending pull
+        // instead of waiting for the source to deliver its next event
+        let event = tokio::select! {
+            event = source.pull(&pull_from) => event?,
+            _ = sender.closed() => break,
+        };
+        let event = if let Some(event) = event {
+            event
+        } else {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_millis(200)) => continue,
+                _ = sender.closed() => break,
+            }
+        };
+        metrics.observe_block_fetch(event_fetch_start.elapsed());
+
+        let new_from = event.next_from().unwrap_or(pull_from);
+        if sender.send(event).await.is_err() {
+            // the processing loop stopped, so there is no one left to process the events
+            break;
+        }
+        pull_from = new_from;
+    }
+
+    Ok(())
+}
+
 #[async_trait]
 impl<
         FromType: PullFrom + Clone,
@@ -75,9 +176,11 @@ impl<
         let _ = self.sink.stop().await.map_err(|err| {
             tracing::error!("Error during sink shutdown: {:?}", err);
         });
-        let _ = self.source.stop().await.map_err(|err| {
-            tracing::error!("Error during source shutdown: {:?}", err);
-        });
+        if let Some(source) = self.source {
+            let _ = source.stop().await.map_err(|err| {
+                tracing::error!("Error during source shutdown: {:?}", err);
+            });
+        }
 
         Ok(())
     }
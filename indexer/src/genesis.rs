@@ -10,32 +10,54 @@ use cml_chain::genesis::byron::parse::parse_genesis_data;
 use cml_crypto::RawBytesEncoding;
 use entity::sea_orm::{DatabaseConnection, DatabaseTransaction, TransactionTrait};
 use migration::DbErr;
-use tasks::utils::TaskPerfAggregator;
+use tasks::genesis::{CardanoGenesis, ShelleyGenesis};
+use tasks::utils::{blake2b256, TaskPerfAggregator};
 use tasks::{execution_plan::ExecutionPlan, genesis::genesis_executor::process_genesis_block};
 
+use crate::CustomNetworkConfig;
+
 const GENESIS_MAINNET: &str = "./genesis/mainnet-byron-genesis.json";
 const GENESIS_PREVIEW: &str = "./genesis/preview-byron-genesis.json";
 const GENESIS_PREPROD: &str = "./genesis/preprod-byron-genesis.json";
 const GENESIS_TESTNET: &str = "./genesis/testnet-byron-genesis.json";
 const GENESIS_SANCHONET: &str = "./genesis/sanchonet-byron-genesis.json";
 
+/// Shelley genesis files are optional
+/// since they contain no initial funds or staking on the public networks
+fn shelley_genesis_path(network: &str) -> String {
+    format!("./genesis/{network}-shelley-genesis.json")
+}
+
 pub async fn process_genesis(
     conn: &DatabaseConnection,
     network: &str,
+    custom_network: Option<&CustomNetworkConfig>,
     exec_plan: Arc<ExecutionPlan>,
 ) -> anyhow::Result<()> {
     // https://github.com/txpipe/oura/blob/67b01e8739ed2927ced270e08daea74b03bcc7f7/src/sources/common.rs#L91
-    let genesis_path = match network {
-        "mainnet" => GENESIS_MAINNET,
-        "testnet" => GENESIS_TESTNET,
-        "preview" => GENESIS_PREVIEW,
-        "preprod" => GENESIS_PREPROD,
-        "sanchonet" => GENESIS_SANCHONET,
-        rest => {
-            return Err(anyhow!(
-                "{} is invalid. NETWORK must be either mainnet/preview/preprod/testnet",
-                rest
-            ))
+    let (genesis_path, shelley_path) = match custom_network {
+        Some(custom_network) => (
+            custom_network.byron_genesis.clone(),
+            custom_network.shelley_genesis.clone(),
+        ),
+        None => {
+            let genesis_path = match network {
+                "mainnet" => GENESIS_MAINNET,
+                "testnet" => GENESIS_TESTNET,
+                "preview" => GENESIS_PREVIEW,
+                "preprod" => GENESIS_PREPROD,
+                "sanchonet" => GENESIS_SANCHONET,
+                rest => {
+                    return Err(anyhow!(
+                        "{} is invalid. NETWORK must be either mainnet/preview/preprod/testnet/sanchonet or custom_network has to be set in the sink config",
+                        rest
+                    ))
+                }
+            };
+            (
+                genesis_path.to_string(),
+                Some(shelley_genesis_path(network)),
+            )
         }
     };
 
@@ -44,11 +66,17 @@ pub async fn process_genesis(
     tracing::info!("Parsing genesis file...");
     let mut time_counter = std::time::Instant::now();
 
-    let file = fs::File::open(genesis_path).expect("Failed to open genesis file");
+    let file = fs::File::open(&genesis_path)
+        .map_err(|err| anyhow!("Failed to open genesis file {}: {}", genesis_path, err))?;
     let genesis_file: Box<GenesisData> = Box::new(
         parse_genesis_data(file).map_err(|err| anyhow!("can't parse genesis data: {:?}", err))?,
     );
 
+    let shelley_genesis = match shelley_path {
+        Some(path) => parse_shelley_genesis(&path)?,
+        None => None,
+    };
+
     tracing::info!(
         "Finished parsing genesis file after {:?}",
         time_counter.elapsed()
@@ -60,6 +88,7 @@ pub async fn process_genesis(
         Box::pin(insert_genesis(
             txn,
             genesis_file,
+            shelley_genesis,
             exec_plan.clone(),
             task_perf_aggregator.clone(),
         ))
@@ -78,9 +107,37 @@ pub async fn process_genesis(
     Ok(())
 }
 
+/// Hash of the Byron genesis file, used as the hash of the genesis block
+pub fn byron_genesis_hash(path: &str) -> anyhow::Result<String> {
+    let file = fs::File::open(path)
+        .map_err(|err| anyhow!("Failed to open genesis file {}: {}", path, err))?;
+    let genesis_file =
+        parse_genesis_data(file).map_err(|err| anyhow!("can't parse genesis data: {:?}", err))?;
+    Ok(hex::encode(genesis_file.genesis_prev.to_raw_bytes()))
+}
+
+fn parse_shelley_genesis(path: &str) -> anyhow::Result<Option<ShelleyGenesis>> {
+    let content = match fs::read(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            tracing::info!("No Shelley genesis file found at {}, skipping it", path);
+            return Ok(None);
+        }
+        Err(err) => return Err(anyhow!("can't read Shelley genesis file {}: {}", path, err)),
+    };
+    let data = cml_chain::genesis::shelley::parse::parse_genesis_data(content.as_slice())
+        .map_err(|err| anyhow!("can't parse Shelley genesis data: {:?}", err))?;
+
+    Ok(Some(ShelleyGenesis {
+        data,
+        hash: blake2b256(&content).to_vec(),
+    }))
+}
+
 pub async fn insert_genesis(
     txn: &DatabaseTransaction,
     genesis_file: Box<GenesisData>,
+    shelley_genesis: Option<ShelleyGenesis>,
     exec_plan: Arc<ExecutionPlan>,
     task_perf_aggregator: Arc<Mutex<TaskPerfAggregator>>,
 ) -> Result<(), DbErr> {
@@ -94,11 +151,17 @@ pub async fn insert_genesis(
         era: EraValue::Byron,
         epoch: None,
         epoch_slot: None,
+        slot_time: None,
+    };
+
+    let genesis = CardanoGenesis {
+        byron: *genesis_file,
+        shelley: shelley_genesis,
     };
 
     process_genesis_block(
         txn,
-        ("", &genesis_file, &block_global_info),
+        ("", &genesis, &block_global_info),
         &exec_plan,
         task_perf_aggregator.clone(),
     )
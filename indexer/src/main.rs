@@ -1,4 +1,5 @@
-use crate::sink::Sink;
+use crate::metrics::Metrics;
+use crate::sink::{ForkDetected, Sink};
 use crate::sinks::CardanoSink;
 use crate::sources::{CardanoSource, OuraSource};
 use crate::types::StoppableService;
@@ -10,15 +11,18 @@ use oura::sources::BearerKind;
 use serde::Deserialize;
 use std::borrow::Cow;
 use std::fs::File;
+use std::net::SocketAddr;
 use std::process::exit;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tasks::execution_plan::ExecutionPlan;
+use tasks::utils::SlotTimeParams;
 use tracing_subscriber::prelude::*;
 
 mod common;
 mod engine;
 mod genesis;
+mod metrics;
 mod perf_aggregator;
 mod sink;
 mod sinks;
@@ -39,6 +43,23 @@ pub struct Cli {
     /// path to config file
     #[clap(short, long, action = clap::ArgAction::SetTrue)]
     verbose: bool,
+
+    /// Add the dependencies missing from the execution plan (with an empty configuration)
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    include_dependencies: bool,
+
+    /// Start even if the execution plan differs from the one recorded in the database
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    allow_plan_change: bool,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+    /// Check the execution plan is valid (known tasks, dependencies, task configurations) and exit
+    CheckPlan,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -59,9 +80,99 @@ pub enum SinkConfig {
         db: DbConfig,
         #[serde(default = "get_env_network")]
         network: String,
+        /// Group blocks into a single database transaction while far from the tip
+        batch: Option<BatchConfig>,
+        /// Parameters of a network carp doesn't know about (ex: private devnet, local cluster).
+        /// `network` is then only used as a label
+        custom_network: Option<CustomNetworkConfig>,
+        /// Max number of blocks that can be rolled back, including when recovering from a fork
+        #[serde(default = "default_max_rollback_depth")]
+        max_rollback_depth: u64,
     },
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(deny_unknown_fields)]
+pub struct BatchConfig {
+    /// Max number of blocks committed in a single database transaction
+    pub max_blocks: usize,
+    /// Max number of seconds a database transaction can be kept open for
+    pub max_duration_secs: u64,
+    /// Blocks are only batched while the indexer is estimated to be more than this many blocks
+    /// behind the tip. Closer to the tip, every block is committed on its own
+    pub min_blocks_behind_tip: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(deny_unknown_fields)]
+pub struct CustomNetworkConfig {
+    /// Protocol magic of the network
+    pub magic: u32,
+    /// Network id of the Shelley addresses (`networkId` of the Shelley genesis: 1 for Mainnet, 0 for Testnet)
+    #[serde(default)]
+    pub network_id: u8,
+    /// Path of the Byron genesis file
+    pub byron_genesis: String,
+    /// Path of the Shelley genesis file (initial funds & staking aren't indexed if unset)
+    pub shelley_genesis: Option<String>,
+    /// Unix time of slot 0 (`startTime` of the Byron genesis)
+    pub system_start: u64,
+    /// Length of a Byron slot in seconds
+    #[serde(default = "default_byron_slot_length")]
+    pub byron_slot_length: u64,
+    /// Number of slots in a Byron epoch
+    #[serde(default = "default_byron_epoch_length")]
+    pub byron_epoch_length: u64,
+    /// First epoch of the Shelley era (0 if the network starts directly in Shelley)
+    #[serde(default)]
+    pub shelley_start_epoch: u64,
+    /// Length of a Shelley slot in seconds
+    #[serde(default = "default_shelley_slot_length")]
+    pub shelley_slot_length: u64,
+    /// Number of slots in a Shelley epoch
+    pub shelley_epoch_length: u64,
+    /// Hash of the first block of the chain. Only needed by the `cardano_net` source
+    pub first_block_hash: Option<String>,
+}
+
+impl CustomNetworkConfig {
+    /// First slot of the Shelley era
+    pub fn shelley_start_slot(&self) -> u64 {
+        self.shelley_start_epoch * self.byron_epoch_length
+    }
+
+    pub fn slot_time_params(&self) -> SlotTimeParams {
+        SlotTimeParams {
+            system_start: self.system_start,
+            byron_slot_length: self.byron_slot_length,
+            shelley_start_slot: self.shelley_start_slot(),
+            shelley_slot_length: self.shelley_slot_length,
+        }
+    }
+
+    /// Bech32 prefix of the Shelley addresses
+    pub fn address_hrp(&self) -> &'static str {
+        match self.network_id {
+            1 => "addr",
+            _ => "addr_test",
+        }
+    }
+}
+
+fn default_byron_slot_length() -> u64 {
+    20
+}
+
+fn default_byron_epoch_length() -> u64 {
+    21600
+}
+
+fn default_shelley_slot_length() -> u64 {
+    1
+}
+
 pub enum Network {}
 
 #[derive(Debug, Clone, Deserialize)]
@@ -81,6 +192,20 @@ pub struct Config {
     /// Starting block hash. This will NOT rollback the database (use the rollback util for that)
     /// This is instead meant to make it easier to write database migrations
     start_block: Option<String>,
+    /// Address to serve prometheus metrics on (ex: 0.0.0.0:9184). Metrics aren't served if unset
+    metrics_address: Option<SocketAddr>,
+    /// Max number of events fetched from the source ahead of the block currently being processed
+    #[serde(default = "default_prefetch_depth")]
+    prefetch_depth: usize,
+}
+
+/// Security parameter (k) of the Cardano networks: blocks deeper than this can't be rolled back
+fn default_max_rollback_depth() -> u64 {
+    2160
+}
+
+fn default_prefetch_depth() -> usize {
+    32
 }
 
 fn get_env_db_url() -> String {
@@ -99,6 +224,9 @@ async fn main() -> anyhow::Result<()> {
         plan,
         config_path,
         verbose,
+        include_dependencies,
+        allow_plan_change,
+        command,
     } = Cli::parse();
 
     let default_trace = if verbose {
@@ -126,6 +254,10 @@ async fn main() -> anyhow::Result<()> {
         .init();
     // End logging setup block
 
+    if let Some(Command::CheckPlan) = command {
+        return check_plan(&plan, include_dependencies);
+    }
+
     let running = Arc::new(AtomicBool::new(true));
     let processing_finished = Arc::new(AtomicBool::new(false));
 
@@ -141,7 +273,7 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("{}", "Starting Carp");
 
     tracing::info!("Execution plan {}", plan);
-    let exec_plan = Arc::new(ExecutionPlan::load_from_file(&plan)?);
+    let exec_plan = Arc::new(ExecutionPlan::load_from_file(&plan, include_dependencies)?);
 
     let config = if let Some(config_path) = config_path {
         tracing::info!("Config file {:?}", config_path);
@@ -169,90 +301,247 @@ async fn main() -> anyhow::Result<()> {
         config
     };
 
-    let (network, mut sink) = match config.sink {
-        SinkConfig::Cardano { ref network, .. } => (
-            network.clone(),
-            CardanoSink::new(config.sink, exec_plan)
-                .await
-                .context("Can't create cardano sink")?,
-        ),
+    let (network, custom_network) = match &config.sink {
+        SinkConfig::Cardano {
+            network,
+            custom_network,
+            ..
+        } => (network.clone(), custom_network.clone()),
     };
 
-    let start_from = sink
+    let metrics =
+        Metrics::new(&network, custom_network.as_ref()).context("Can't register metrics")?;
+    let _metrics_server = config
+        .metrics_address
+        .map(Metrics::start_server)
+        .transpose()?;
+
+    let mut sink = CardanoSink::new(config.sink, exec_plan, allow_plan_change, metrics.clone())
+        .await
+        .context("Can't create cardano sink")?;
+
+    let mut start_from = sink
         .start_from(config.start_block)
         .await
         .context("Can't get starting point from sink")?;
-
-    match &config.source {
-        SourceConfig::Oura { .. } => {
-            let source = OuraSource::new(config.source, network, start_from.clone())
+    let mut fork_recovery = false;
+
+    // if the source switches to a fork that diverged before the latest stored blocks,
+    // it's restarted from recent blocks so it resumes from the deepest common ancestor
+    loop {
+        let forked_sink = match &config.source {
+            SourceConfig::Oura { .. } => {
+                let source = OuraSource::new(
+                    config.source.clone(),
+                    network.clone(),
+                    custom_network.clone(),
+                    start_from.clone(),
+                )
                 .context("Can't create oura source")?;
-            let start_from = start_from
-                .last()
-                .cloned()
-                .ok_or_else(|| anyhow!("Starting points list is empty"))?;
-
-            main_loop(source, sink, start_from, running, processing_finished).await
-        }
-        SourceConfig::CardanoNet { relay } => {
-            let base_config = match network.as_ref() {
-                "mainnet" => dcspark_blockchain_source::cardano::NetworkConfiguration::mainnet(),
-                "preprod" => dcspark_blockchain_source::cardano::NetworkConfiguration::preprod(),
-                "preview" => dcspark_blockchain_source::cardano::NetworkConfiguration::preview(),
-                "sanchonet" => dcspark_blockchain_source::cardano::NetworkConfiguration::sancho(),
-                _ => return Err(anyhow::anyhow!("network not supported by source")),
-            };
-
-            // try to find a confirmed point.
-            //
-            // this way the multiverse can be temporary, which saves setting up the extra db
-            // (at the expense of repulling some extra blocks at startup)
-            let start_from = sink
-                .get_latest_points(15)
-                .await?
-                .last()
-                .cloned()
-                .ok_or_else(|| anyhow!("Starting points list is empty"))?;
-
-            let network_config = dcspark_blockchain_source::cardano::NetworkConfiguration {
-                relay: relay.clone(),
-                from: start_from.clone(),
-                ..base_config
-            };
-
-            let source = CardanoSource::new(network_config).await?;
-
-            main_loop(source, sink, start_from, running, processing_finished).await
+                let start_from = start_from
+                    .last()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Starting points list is empty"))?;
+
+                main_loop(
+                    source,
+                    sink,
+                    start_from,
+                    running.clone(),
+                    metrics.clone(),
+                    config.prefetch_depth,
+                )
+                .await
+            }
+            SourceConfig::CardanoNet { relay } => {
+                let base_config = match (network.as_ref(), &custom_network) {
+                    (_, Some(custom_network)) => custom_network_configuration(custom_network)?,
+                    ("mainnet", None) => {
+                        dcspark_blockchain_source::cardano::NetworkConfiguration::mainnet()
+                    }
+                    ("preprod", None) => {
+                        dcspark_blockchain_source::cardano::NetworkConfiguration::preprod()
+                    }
+                    ("preview", None) => {
+                        dcspark_blockchain_source::cardano::NetworkConfiguration::preview()
+                    }
+                    ("sanchonet", None) => {
+                        dcspark_blockchain_source::cardano::NetworkConfiguration::sancho()
+                    }
+                    _ => return Err(anyhow::anyhow!("network not supported by source")),
+                };
+
+                let base_config = dcspark_blockchain_source::cardano::NetworkConfiguration {
+                    relay: relay.clone(),
+                    ..base_config
+                };
+
+                let (source, start_from) = match fork_recovery {
+                    // the most recent recovery point known by the node is the common ancestor
+                    true => connect_from_newest_known_point(base_config, &start_from).await?,
+                    // try to find a confirmed point.
+                    //
+                    // this way the multiverse can be temporary, which saves setting up the extra db
+                    // (at the expense of repulling some extra blocks at startup)
+                    false => {
+                        let start_from = sink
+                            .get_latest_points(15)
+                            .await?
+                            .last()
+                            .cloned()
+                            .ok_or_else(|| anyhow!("Starting points list is empty"))?;
+                        let network_config =
+                            dcspark_blockchain_source::cardano::NetworkConfiguration {
+                                from: start_from.clone(),
+                                ..base_config
+                            };
+                        (CardanoSource::new(network_config).await?, start_from)
+                    }
+                };
+
+                main_loop(
+                    source,
+                    sink,
+                    start_from,
+                    running.clone(),
+                    metrics.clone(),
+                    config.prefetch_depth,
+                )
+                .await
+            }
+        };
+
+        match forked_sink {
+            Some(forked_sink) => {
+                sink = forked_sink;
+                start_from = sink.fork_recovery_points().await?;
+                fork_recovery = true;
+                tracing::info!(
+                    "Restarting the source from {} recent blocks to find a common ancestor. If none of them is found, the fork is deeper than max_rollback_depth",
+                    start_from.len()
+                );
+            }
+            None => break,
         }
-    };
+    }
+    processing_finished.store(true, Ordering::SeqCst);
 
     Ok(())
 }
 
+/// Network configuration of the `cardano_net` source for a custom network
+fn custom_network_configuration(
+    custom_network: &CustomNetworkConfig,
+) -> anyhow::Result<dcspark_blockchain_source::cardano::NetworkConfiguration> {
+    let first_block_hash = custom_network.first_block_hash.as_ref().ok_or_else(|| {
+        anyhow!("custom_network.first_block_hash has to be set to use the cardano_net source")
+    })?;
+    // the genesis block inserted by carp is identified by the hash of the Byron genesis
+    let genesis_hash = genesis::byron_genesis_hash(&custom_network.byron_genesis)?;
+
+    // start from a testnet configuration so that the parameters a custom network can't change
+    // (ex: address prefix) are set
+    let base_config = dcspark_blockchain_source::cardano::NetworkConfiguration::preview();
+    let shelley_start_slot = custom_network.shelley_start_slot();
+
+    Ok(dcspark_blockchain_source::cardano::NetworkConfiguration {
+        chain_info: cardano_sdk::chaininfo::ChainInfo {
+            protocol_magic: custom_network.magic.into(),
+            ..base_config.chain_info
+        },
+        genesis_parent: dcspark_core::BlockId::new(genesis_hash),
+        genesis: dcspark_core::BlockId::new(first_block_hash.clone()),
+        shelley_era_config: dcspark_blockchain_source::cardano::time::Era {
+            first_slot: shelley_start_slot,
+            start_epoch: custom_network.shelley_start_epoch,
+            known_time: custom_network.system_start
+                + shelley_start_slot * custom_network.byron_slot_length,
+            slot_length: custom_network.shelley_slot_length,
+            epoch_length_seconds: custom_network.shelley_epoch_length
+                * custom_network.shelley_slot_length,
+        },
+        ..base_config
+    })
+}
+
+/// The `cardano_net` source intersects the chain of the node at a single point,
+/// so the points (sorted from newest to oldest) are tried one by one until the node knows one
+async fn connect_from_newest_known_point(
+    base_config: dcspark_blockchain_source::cardano::NetworkConfiguration,
+    points: &[<CardanoSink as Sink>::From],
+) -> anyhow::Result<(CardanoSource, <CardanoSink as Sink>::From)> {
+    for point in points {
+        let network_config = dcspark_blockchain_source::cardano::NetworkConfiguration {
+            from: point.clone(),
+            ..base_config.clone()
+        };
+        match CardanoSource::new(network_config).await {
+            Ok(source) => return Ok((source, point.clone())),
+            Err(error) => tracing::debug!(%error, ?point, "Recovery point isn't known by the node"),
+        }
+    }
+    Err(anyhow!(
+        "None of the {} recovery points is known by the node, the fork is deeper than max_rollback_depth",
+        points.len()
+    ))
+}
+
+fn check_plan(plan: &str, include_dependencies: bool) -> anyhow::Result<()> {
+    let mut exec_plan = ExecutionPlan::load_from_file_unchecked(plan)?;
+    match exec_plan
+        .sort_tasks(include_dependencies)
+        .and_then(|()| exec_plan.validate())
+    {
+        Ok(()) => {
+            tracing::info!("Execution plan {} is valid", plan);
+            Ok(())
+        }
+        Err(errors) => {
+            for error in errors.0.iter() {
+                tracing::error!("{}", error);
+            }
+            Err(anyhow!(
+                "Execution plan {} has {} error(s)",
+                plan,
+                errors.0.len()
+            ))
+        }
+    }
+}
+
+/// Returns the sink if the source has to be restarted to recover from a fork
 async fn main_loop<S>(
     source: S,
     sink: CardanoSink,
     start_from: <S as Source>::From,
     running: Arc<AtomicBool>,
-    processing_finished: Arc<AtomicBool>,
-) where
+    metrics: Metrics,
+    prefetch_depth: usize,
+) -> Option<CardanoSink>
+where
     S: Source<From = <CardanoSink as Sink>::From, Event = <CardanoSink as Sink>::Event>
         + StoppableService
-        + Send,
+        + Send
+        + 'static,
     <S as Source>::Event: GetNextFrom,
     <S as Source>::From: Clone,
 {
-    let mut engine = engine::FetchEngine::new(source, sink, running);
+    let mut engine = engine::FetchEngine::new(source, sink, running, metrics, prefetch_depth);
 
-    if let Err(error) = engine.fetch_and_process(start_from).await {
-        tracing::error!(%error, "Processing loop finished with error, stopping engine");
-    } else {
-        tracing::info!("Processing loop finished successfully, stopping engine");
+    match engine.fetch_and_process(start_from).await {
+        Err(error) if error.downcast_ref::<ForkDetected>().is_some() => {
+            tracing::warn!(%error, "Fork detected, stopping the source");
+            return Some(engine.stop_source().await);
+        }
+        Err(error) => {
+            tracing::error!(%error, "Processing loop finished with error, stopping engine");
+        }
+        Ok(()) => tracing::info!("Processing loop finished successfully, stopping engine"),
     }
     if let Err(error) = engine.stop().await {
         tracing::error!(%error, "Couldn't stop engine successfully");
     } else {
         tracing::info!("Engine is stopped successfully");
     }
-    processing_finished.store(true, Ordering::SeqCst);
+    None
 }
@@ -0,0 +1,151 @@
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use prometheus_exporter::prometheus::{
+    register_counter, register_counter_vec, register_int_counter, register_int_gauge, Counter,
+    CounterVec, IntCounter, IntGauge,
+};
+use tasks::utils::{SlotTimeParams, TaskPerfAggregator};
+
+use crate::CustomNetworkConfig;
+
+/// Cumulative indexer metrics exposed through the prometheus endpoint.
+/// Unlike `PerfAggregator` and `TaskPerfAggregator`, these are never reset at epoch boundaries
+#[derive(Clone)]
+pub struct Metrics {
+    task_duration: CounterVec,
+    block_fetch_duration: Counter,
+    block_parse_duration: Counter,
+    rollback_duration: Counter,
+    blocks_processed: IntCounter,
+    txs_processed: IntCounter,
+    rollbacks: IntCounter,
+    current_slot: IntGauge,
+    current_epoch: IntGauge,
+    current_height: IntGauge,
+    tip_lag: IntGauge,
+
+    slot_time: Option<SlotTimeParams>,
+}
+
+impl Metrics {
+    /// Registers all the metrics in the default prometheus registry.
+    /// note: this can only be called once per process
+    pub fn new(
+        network: &str,
+        custom_network: Option<&CustomNetworkConfig>,
+    ) -> anyhow::Result<Self> {
+        let slot_time = slot_time_params(network, custom_network);
+        if slot_time.is_none() {
+            tracing::warn!(
+                "Unknown slot parameters for network {network}, tip lag won't be reported"
+            );
+        }
+
+        Ok(Self {
+            task_duration: register_counter_vec!(
+                "carp_task_duration_seconds_total",
+                "Time spent running each task of the execution plan",
+                &["task"]
+            )?,
+            block_fetch_duration: register_counter!(
+                "carp_block_fetch_duration_seconds_total",
+                "Time spent waiting for the source to return the next event"
+            )?,
+            block_parse_duration: register_counter!(
+                "carp_block_parse_duration_seconds_total",
+                "Time spent decoding block cbor"
+            )?,
+            rollback_duration: register_counter!(
+                "carp_rollback_duration_seconds_total",
+                "Time spent rolling back the database"
+            )?,
+            blocks_processed: register_int_counter!(
+                "carp_blocks_processed_total",
+                "Number of blocks inserted in the database"
+            )?,
+            txs_processed: register_int_counter!(
+                "carp_txs_processed_total",
+                "Number of transactions inserted in the database"
+            )?,
+            rollbacks: register_int_counter!("carp_rollbacks_total", "Number of rollback events")?,
+            current_slot: register_int_gauge!(
+                "carp_current_slot",
+                "Slot of the latest block inserted in the database"
+            )?,
+            current_epoch: register_int_gauge!(
+                "carp_current_epoch",
+                "Epoch of the latest block inserted in the database"
+            )?,
+            current_height: register_int_gauge!(
+                "carp_current_height",
+                "Height of the latest block inserted in the database"
+            )?,
+            tip_lag: register_int_gauge!(
+                "carp_tip_lag_seconds",
+                "Wall-clock time elapsed since the slot of the latest inserted block"
+            )?,
+            slot_time,
+        })
+    }
+
+    /// Serves the default prometheus registry on `http://{address}/metrics`
+    /// note: the server keeps running only as long as the returned exporter is alive
+    pub fn start_server(address: SocketAddr) -> anyhow::Result<prometheus_exporter::Exporter> {
+        let exporter = prometheus_exporter::start(address)
+            .map_err(|err| anyhow!("Can't start metrics server on {address}: {err}"))?;
+        tracing::info!("Serving metrics on http://{}/metrics", address);
+        Ok(exporter)
+    }
+
+    pub fn observe_block_fetch(&self, duration: Duration) {
+        self.block_fetch_duration.inc_by(duration.as_secs_f64());
+    }
+
+    pub fn observe_block_parse(&self, duration: Duration) {
+        self.block_parse_duration.inc_by(duration.as_secs_f64());
+    }
+
+    pub fn observe_rollback(&self, duration: Duration) {
+        self.rollbacks.inc();
+        self.rollback_duration.inc_by(duration.as_secs_f64());
+    }
+
+    pub fn observe_tasks(&self, task_perf_aggregator: &TaskPerfAggregator) {
+        for (task, duration) in task_perf_aggregator.0.iter() {
+            self.task_duration
+                .with_label_values(&[task])
+                .inc_by(duration.as_secs_f64());
+        }
+    }
+
+    pub fn observe_block(&self, slot: u64, epoch: Option<u64>, height: u64, tx_count: usize) {
+        self.blocks_processed.inc();
+        self.txs_processed.inc_by(tx_count as u64);
+        self.current_slot.set(slot as i64);
+        self.current_height.set(height as i64);
+        if let Some(epoch) = epoch {
+            self.current_epoch.set(epoch as i64);
+        }
+        if let Some(slot_time) = &self.slot_time {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            self.tip_lag
+                .set(now.saturating_sub(slot_time.slot_to_unix_time(slot)) as i64);
+        }
+    }
+}
+
+/// Slot parameters of the custom network if there is one, otherwise of the known `network`
+pub fn slot_time_params(
+    network: &str,
+    custom_network: Option<&CustomNetworkConfig>,
+) -> Option<SlotTimeParams> {
+    match custom_network {
+        Some(custom_network) => Some(custom_network.slot_time_params()),
+        None => SlotTimeParams::for_network(network),
+    }
+}
@@ -1,6 +1,8 @@
 use crate::perf_aggregator::PerfAggregator;
 use async_trait::async_trait;
 use dcspark_blockchain_source::{EventObject, PullFrom};
+use std::fmt;
+use std::time::Instant;
 
 #[async_trait]
 pub trait Sink {
@@ -13,4 +15,37 @@ pub trait Sink {
         event: Self::Event,
         perf_aggregator: &mut PerfAggregator,
     ) -> anyhow::Result<()>;
+
+    /// Time at which the processed events have to be flushed even if no new event arrives
+    /// (ex: a batch that isn't full yet while the chain is quiet)
+    fn flush_deadline(&self) -> Option<Instant> {
+        None
+    }
+
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Points to restart the source from after a `ForkDetected` error, sorted from newest to oldest.
+    /// The source resumes from the most recent of them that is part of its chain
+    async fn fork_recovery_points(&self) -> anyhow::Result<Vec<Self::From>>;
+}
+
+/// The source switched to a fork the sink doesn't know about
+/// (ex: it rolled back to a block that isn't stored)
+#[derive(Debug)]
+pub struct ForkDetected {
+    pub block_hash: String,
+}
+
+impl fmt::Display for ForkDetected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the source rolled back to block {} which isn't stored",
+            self.block_hash
+        )
+    }
 }
+
+impl std::error::Error for ForkDetected {}
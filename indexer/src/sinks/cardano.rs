@@ -1,8 +1,9 @@
 use crate::common::CardanoEventType;
+use crate::metrics::{slot_time_params, Metrics};
 use crate::perf_aggregator::PerfAggregator;
-use crate::sink::Sink;
+use crate::sink::{ForkDetected, Sink};
 use crate::types::{MultiEraBlock, StoppableService};
-use crate::{genesis, DbConfig, SinkConfig};
+use crate::{genesis, BatchConfig, CustomNetworkConfig, DbConfig, SinkConfig};
 use async_trait::async_trait;
 
 use dcspark_blockchain_source::cardano::Point;
@@ -14,34 +15,80 @@ use entity::{
     sea_orm::{prelude::*, ColumnTrait, DatabaseTransaction, TransactionTrait},
 };
 use entity::{
-    prelude::{Block, BlockColumn},
-    sea_orm::{DatabaseConnection, EntityTrait, QueryOrder, QuerySelect},
+    execution_plan,
+    prelude::{Block, BlockColumn, ExecutionPlanColumn},
+    sea_orm::{DatabaseConnection, EntityTrait, QueryOrder, QuerySelect, Set},
 };
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tasks::byron::byron_executor::process_byron_block;
+use tasks::config::UnspentOutputConfig::UnspentOutputConfig;
 use tasks::dsl::database_task::BlockGlobalInfo;
 use tasks::execution_plan::ExecutionPlan;
 use tasks::multiera::multiera_executor::process_multiera_block;
-use tasks::utils::TaskPerfAggregator;
+use tasks::task_ranges::{extend_task_ranges, shrink_task_ranges, start_task_ranges};
+use tasks::utils::{blake2b256, SlotTimeParams, TaskPerfAggregator};
+
+/// On average, a block is produced every 20 seconds in every era of every known network
+const AVERAGE_BLOCK_TIME_SECS: u64 = 20;
+
+/// Blocks inserted in a database transaction that wasn't committed yet
+struct PendingBatch {
+    txn: DatabaseTransaction,
+    start_time: Instant,
+    // (slot, epoch, height, tx_count) of each block, reported to metrics once committed
+    blocks: Vec<(u64, Option<u64>, u64, usize)>,
+    block_parse: Duration,
+    task_perf_aggregator: Arc<Mutex<TaskPerfAggregator>>,
+}
 
 pub struct CardanoSink {
     db: DatabaseConnection,
     network: String,
+    custom_network: Option<CustomNetworkConfig>,
     exec_plan: Arc<ExecutionPlan>,
+    allow_plan_change: bool,
+    max_rollback_depth: u64,
+    // TaskBlockRange rows extended by the blocks being added
+    task_ranges: Vec<i32>,
+
+    batch_config: Option<BatchConfig>,
+    slot_time: Option<SlotTimeParams>,
+    pending_batch: Option<PendingBatch>,
+    is_batching: bool,
 
     last_epoch: i128,
     epoch_start_time: std::time::Instant,
     task_perf_aggregator: Arc<Mutex<TaskPerfAggregator>>,
+    metrics: Metrics,
 }
 
 impl CardanoSink {
     #[allow(unreachable_patterns)]
-    pub async fn new(config: SinkConfig, exec_plan: Arc<ExecutionPlan>) -> anyhow::Result<Self> {
-        let (db_config, network) = match config {
-            SinkConfig::Cardano { db, network } => (db, network),
+    pub async fn new(
+        config: SinkConfig,
+        exec_plan: Arc<ExecutionPlan>,
+        allow_plan_change: bool,
+        metrics: Metrics,
+    ) -> anyhow::Result<Self> {
+        let (db_config, network, batch_config, custom_network, max_rollback_depth) = match config {
+            SinkConfig::Cardano {
+                db,
+                network,
+                batch,
+                custom_network,
+                max_rollback_depth,
+            } => (db, network, batch, custom_network, max_rollback_depth),
             _ => todo!("Invalid sink config provided"),
         };
+        check_prune_depth(&exec_plan, max_rollback_depth)?;
+        let slot_time = slot_time_params(&network, custom_network.as_ref());
+        if batch_config.is_some() && slot_time.is_none() {
+            tracing::warn!(
+                "Unknown slot parameters for network {network}, blocks won't be batched"
+            );
+        }
         match db_config {
             DbConfig::Postgres { database_url } => {
                 let conn = Database::connect(&database_url).await?;
@@ -49,10 +96,19 @@ impl CardanoSink {
                 Ok(Self {
                     db: conn,
                     network,
+                    custom_network,
                     exec_plan,
+                    allow_plan_change,
+                    max_rollback_depth,
+                    task_ranges: vec![],
+                    batch_config,
+                    slot_time,
+                    pending_batch: None,
+                    is_batching: false,
                     last_epoch: -1,
                     epoch_start_time: std::time::Instant::now(),
                     task_perf_aggregator: Arc::new(Mutex::new(TaskPerfAggregator::default())),
+                    metrics,
                 })
             }
             _ => todo!("Only postgres is supported atm"),
@@ -87,15 +143,170 @@ impl CardanoSink {
         // start of Alonzo: 8959c0323b94cc670afe44222ab8b4e72cfcad3b5ab665f334bbe642dc6e9ef4
     }
 
+    /// Records the execution plan in the database, or checks it matches the one already recorded.
+    /// Changing the plan of a database that already has blocks is refused unless
+    /// `allow_plan_change` is set since tasks added/removed would have data for only part of the chain
+    async fn check_execution_plan(&self) -> anyhow::Result<()> {
+        let latest_block = Block::find()
+            .order_by_desc(BlockColumn::Id)
+            .one(&self.db)
+            .await?;
+        let next_height = latest_block
+            .as_ref()
+            .map(|block| block.height + 1)
+            .unwrap_or(0);
+
+        // plans recorded after the latest block weren't used for any block left in the database
+        // (ex: after using the rollback util)
+        execution_plan::Entity::delete_many()
+            .filter(ExecutionPlanColumn::StartHeight.gt(next_height))
+            .exec(&self.db)
+            .await?;
+
+        let plan = self.exec_plan.to_toml();
+        let hash = blake2b256(plan.as_bytes()).to_vec();
+
+        // note: the backfill util can split plans, so ids don't follow the heights
+        let previous = execution_plan::Entity::find()
+            .order_by_desc(ExecutionPlanColumn::StartHeight)
+            .one(&self.db)
+            .await?;
+        match previous {
+            None => {
+                if latest_block.is_some() {
+                    tracing::warn!(
+                        "No execution plan recorded for the blocks already in the database"
+                    );
+                }
+            }
+            Some(previous) if previous.hash == hash => {
+                if previous.end_height.is_some() {
+                    let mut previous: execution_plan::ActiveModel = previous.into();
+                    previous.end_height = Set(None);
+                    previous.update(&self.db).await?;
+                }
+                return Ok(());
+            }
+            Some(previous) => {
+                let changes = ExecutionPlan::from_toml(&previous.plan)
+                    .map(|previous_plan| self.exec_plan.changes_since(&previous_plan))
+                    .unwrap_or_else(|_| vec!["previous plan can't be parsed".to_string()]);
+                if latest_block.is_some() && !changes.is_empty() {
+                    let changes = changes.join("\n  - ");
+                    if !self.allow_plan_change {
+                        return Err(anyhow::anyhow!(
+                            "Execution plan differs from the one used since height {}:\n  - {}\nUse --allow-plan-change to index the next blocks with it anyway",
+                            previous.start_height,
+                            changes
+                        ));
+                    }
+                    tracing::warn!(
+                        "Execution plan differs from the one used since height {}, blocks after height {} will use the new plan:\n  - {}",
+                        previous.start_height,
+                        next_height - 1,
+                        changes
+                    );
+                }
+
+                if previous.start_height >= next_height {
+                    // no block was indexed with the previous plan
+                    execution_plan::Entity::delete_by_id(previous.id)
+                        .exec(&self.db)
+                        .await?;
+                } else {
+                    let mut previous: execution_plan::ActiveModel = previous.into();
+                    previous.end_height = Set(Some(next_height - 1));
+                    previous.update(&self.db).await?;
+                }
+            }
+        }
+
+        execution_plan::ActiveModel {
+            hash: Set(hash),
+            plan: Set(plan),
+            carp_version: Set(env!("CARGO_PKG_VERSION").to_string()),
+            start_height: Set(next_height),
+            end_height: Set(None),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Picks the `TaskBlockRange` row of every task of the plan that the next blocks extend
+    /// (see `start_task_ranges`), after removing the blocks no longer in the database from the ranges
+    async fn record_task_ranges(&mut self, txn: &DatabaseTransaction) -> anyhow::Result<()> {
+        let next_height = Block::find()
+            .order_by_desc(BlockColumn::Id)
+            .one(txn)
+            .await?
+            .map(|block| block.height + 1)
+            .unwrap_or(0);
+
+        shrink_task_ranges(txn, next_height).await?;
+        self.task_ranges = start_task_ranges(txn, &self.exec_plan, next_height).await?;
+
+        Ok(())
+    }
+
+    /// Whether the block at this slot is far enough from the tip to be batched with other blocks
+    fn should_batch(&self, block_slot: u64) -> bool {
+        let (batch_config, slot_time) = match (&self.batch_config, &self.slot_time) {
+            (Some(batch_config), Some(slot_time)) => (batch_config, slot_time),
+            _ => return false,
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let blocks_behind_tip =
+            now.saturating_sub(slot_time.slot_to_unix_time(block_slot)) / AVERAGE_BLOCK_TIME_SECS;
+        blocks_behind_tip > batch_config.min_blocks_behind_tip
+    }
+
+    fn is_batch_full(&self, batch: &PendingBatch) -> bool {
+        match &self.batch_config {
+            Some(batch_config) => {
+                batch.blocks.len() >= batch_config.max_blocks
+                    || batch.start_time.elapsed()
+                        >= Duration::from_secs(batch_config.max_duration_secs)
+            }
+            None => true,
+        }
+    }
+
+    /// Commits the blocks inserted since the last commit (if any)
+    async fn commit_pending_batch(&mut self) -> anyhow::Result<()> {
+        let batch = match self.pending_batch.take() {
+            Some(batch) => batch,
+            None => return Ok(()),
+        };
+        batch.txn.commit().await?;
+
+        let batch_task_perf_aggregator = batch.task_perf_aggregator.lock().unwrap();
+        self.metrics.observe_tasks(&batch_task_perf_aggregator);
+        self.metrics.observe_block_parse(batch.block_parse);
+        self.task_perf_aggregator
+            .lock()
+            .unwrap()
+            .merge(&batch_task_perf_aggregator);
+        for (slot, epoch, height, tx_count) in batch.blocks {
+            self.metrics.observe_block(slot, epoch, height, tx_count);
+        }
+
+        Ok(())
+    }
+
     async fn get_specific_point(&self, block_hash: &String) -> anyhow::Result<Vec<Point>> {
         let provided_point = Block::find()
             .filter(BlockColumn::Hash.eq(hex::decode(block_hash).unwrap()))
             .one(&self.db)
             .await?;
 
-        if provided_point.is_none() {
-            panic!("Block not found in database: {block_hash}");
-        }
+        let provided_point = provided_point
+            .ok_or_else(|| anyhow::anyhow!("Block not found in database: {block_hash}"))?;
 
         // for the intersection, we need to provide the block BEFORE the one the user passed in
         // since for cardano-node the block represents the last known point
@@ -103,7 +314,7 @@ impl CardanoSink {
 
         // note: may be empty is user passed in genesis block hash
         let points: Vec<Point> = Block::find()
-            .filter(BlockColumn::Id.lt(provided_point.unwrap().id))
+            .filter(BlockColumn::Id.lt(provided_point.id))
             .order_by_desc(BlockColumn::Id)
             .one(&self.db)
             .await?
@@ -124,13 +335,24 @@ impl Sink for CardanoSink {
     type Event = CardanoEventType;
 
     async fn start_from(&mut self, from: Option<String>) -> anyhow::Result<Vec<Self::From>> {
+        self.check_execution_plan().await?;
+        let txn = self.db.begin().await?;
+        self.record_task_ranges(&txn).await?;
+        txn.commit().await?;
+
         let start = match &from {
             None => self.get_latest_point().await?,
             Some(block) => self.get_specific_point(block).await?,
         };
 
         if start.is_empty() {
-            genesis::process_genesis(&self.db, &self.network, self.exec_plan.clone()).await?;
+            genesis::process_genesis(
+                &self.db,
+                &self.network,
+                self.custom_network.as_ref(),
+                self.exec_plan.clone(),
+            )
+            .await?;
             return self.get_latest_point().await;
         }
 
@@ -149,7 +371,7 @@ impl Sink for CardanoSink {
                 epoch_slot,
                 block_number,
                 block_hash,
-                block_slot: _block_slot,
+                block_slot,
             } => {
                 match epoch {
                     Some(epoch) if epoch as i128 > self.last_epoch => {
@@ -192,18 +414,58 @@ impl Sink for CardanoSink {
                     }
                     _ => (),
                 };
-                self.db
-                    .transaction::<_, (), DbErr>(|txn| {
-                        Box::pin(insert_block(
-                            cbor_hex,
-                            epoch,
-                            epoch_slot,
-                            txn,
-                            self.exec_plan.clone(),
-                            self.task_perf_aggregator.clone(),
-                        ))
-                    })
-                    .await?;
+                let should_batch = self.should_batch(block_slot);
+                if should_batch != self.is_batching {
+                    match should_batch {
+                        true => tracing::info!(
+                            "Far from the tip, committing blocks in batches from block #{}",
+                            block_number
+                        ),
+                        false => tracing::info!(
+                            "Close to the tip, committing blocks one by one from block #{}",
+                            block_number
+                        ),
+                    };
+                    self.is_batching = should_batch;
+                }
+
+                // note: blocks of a batch are inserted sequentially in the same transaction
+                // so tasks still see the data written by the previous blocks of the batch
+                let mut batch = match self.pending_batch.take() {
+                    Some(batch) => batch,
+                    None => PendingBatch {
+                        txn: self.db.begin().await?,
+                        start_time: Instant::now(),
+                        blocks: vec![],
+                        block_parse: Duration::ZERO,
+                        // tasks report to a per-batch aggregator so metrics only count commits
+                        task_perf_aggregator: Arc::new(Mutex::new(TaskPerfAggregator::default())),
+                    },
+                };
+                // note: updated in the same transaction so the ranges always match the committed blocks
+                extend_task_ranges(&batch.txn, &self.task_ranges, block_number as i32).await?;
+                // note: on error, the transaction is dropped which rolls back the whole batch
+                let (tx_count, block_parse) = insert_block(
+                    cbor_hex,
+                    epoch,
+                    epoch_slot,
+                    self.slot_time,
+                    &batch.txn,
+                    self.exec_plan.clone(),
+                    batch.task_perf_aggregator.clone(),
+                )
+                .await?;
+                perf_aggregator.block_parse += block_parse;
+                batch.block_parse += block_parse;
+                batch
+                    .blocks
+                    .push((block_slot, epoch, block_number, tx_count));
+
+                let is_batch_full = self.is_batch_full(&batch);
+                self.pending_batch = Some(batch);
+                if !should_batch || is_batch_full {
+                    self.commit_pending_batch().await?;
+                }
             }
             CardanoEventType::RollBack {
                 block_slot,
@@ -219,8 +481,11 @@ impl Sink for CardanoSink {
                 };
                 let rollback_start = std::time::Instant::now();
 
+                // rollbacks happen close to the tip so the batch would be committed soon anyway
+                self.commit_pending_batch().await?;
+
                 let point = Block::find()
-                    .filter(BlockColumn::Hash.eq(hex::decode(block_hash).unwrap()))
+                    .filter(BlockColumn::Hash.eq(hex::decode(&block_hash).unwrap()))
                     .one(&self.db)
                     .await?;
                 match &point {
@@ -228,31 +493,112 @@ impl Sink for CardanoSink {
                         // note: potentially caused by https://github.com/txpipe/oura/issues/304
                         let count = Block::find().count(&self.db).await?;
                         if count > 1 {
-                            panic!(
-                                "Rollback destination did not exist. Maybe you're stuck on a fork?"
-                            );
+                            // the source is on a fork that diverged before the blocks we stored
+                            // so the source has to be restarted from a common ancestor
+                            return Err(ForkDetected { block_hash }.into());
                         }
                     }
                     Some(point) => {
+                        let rollback_depth = Block::find()
+                            .filter(BlockColumn::Id.gt(point.id))
+                            .count(&self.db)
+                            .await?;
+                        if rollback_depth > self.max_rollback_depth {
+                            return Err(anyhow::anyhow!(
+                                "Rolling back to block {} would remove {} blocks which is more than max_rollback_depth ({})",
+                                block_hash,
+                                rollback_depth,
+                                self.max_rollback_depth
+                            ));
+                        }
+                        // note: in one transaction so the ranges always match the stored blocks
+                        let txn = self.db.begin().await?;
                         Block::delete_many()
                             .filter(BlockColumn::Id.gt(point.id))
-                            .exec(&self.db)
+                            .exec(&txn)
                             .await?;
+                        self.record_task_ranges(&txn).await?;
+                        txn.commit().await?;
                     }
                 }
 
-                perf_aggregator.rollback += rollback_start.elapsed();
+                let rollback_duration = rollback_start.elapsed();
+                perf_aggregator.rollback += rollback_duration;
+                self.metrics.observe_rollback(rollback_duration);
             }
         }
         Ok(())
     }
+
+    /// A batch is committed after `max_duration_secs` even if the next block takes a while to arrive
+    fn flush_deadline(&self) -> Option<Instant> {
+        let batch = self.pending_batch.as_ref()?;
+        let batch_config = self.batch_config.as_ref()?;
+        Some(batch.start_time + Duration::from_secs(batch_config.max_duration_secs))
+    }
+
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        self.commit_pending_batch().await
+    }
+
+    /// Recent blocks at exponentially increasing depths (0, 1, 2, 4, ...) up to max_rollback_depth
+    /// so that the common ancestor with the source is found without sending thousands of points
+    async fn fork_recovery_points(&self) -> anyhow::Result<Vec<Self::From>> {
+        let latest_block = match Block::find()
+            .order_by_desc(BlockColumn::Id)
+            .one(&self.db)
+            .await?
+        {
+            Some(block) => block,
+            None => return Ok(vec![]),
+        };
+
+        let mut heights = vec![latest_block.height];
+        let mut depth = 1;
+        while depth < self.max_rollback_depth {
+            heights.push(latest_block.height - depth as i32);
+            depth *= 2;
+        }
+        heights.push(latest_block.height - self.max_rollback_depth as i32);
+        heights.retain(|height| *height >= 0);
+
+        let points: Vec<Point> = Block::find()
+            .filter(BlockColumn::Height.is_in(heights))
+            .order_by_desc(BlockColumn::Id)
+            .all(&self.db)
+            .await?
+            .iter()
+            .map(|block| Point::BlockHeader {
+                slot_nb: SlotNumber::new(block.slot as u64),
+                hash: BlockId::new(hex::encode(&block.hash)),
+            })
+            .collect();
+
+        Ok(points)
+    }
 }
 
 #[async_trait]
 impl StoppableService for CardanoSink {
-    async fn stop(self) -> anyhow::Result<()> {
-        Ok(())
+    async fn stop(mut self) -> anyhow::Result<()> {
+        self.commit_pending_batch().await
+    }
+}
+
+/// Spent outputs pruned by the unspent output task couldn't be restored by a rollback deeper than its prune depth
+fn check_prune_depth(exec_plan: &ExecutionPlan, max_rollback_depth: u64) -> anyhow::Result<()> {
+    let config = match exec_plan.0.get("MultieraUnspentOutputTask") {
+        Some(config) => config.clone().try_into::<UnspentOutputConfig>()?,
+        None => return Ok(()),
+    };
+    if config.prune_depth < max_rollback_depth {
+        return Err(anyhow::anyhow!(
+            "prune_depth of MultieraUnspentOutputTask ({}) is lower than max_rollback_depth ({})",
+            config.prune_depth,
+            max_rollback_depth
+        ));
     }
+    Ok(())
 }
 
 fn to_era_value(x: &MultiEraBlock) -> EraValue {
@@ -271,12 +617,11 @@ async fn insert_block(
     cbor_hex: String,
     epoch: Option<u64>,
     epoch_slot: Option<u64>,
+    slot_time: Option<SlotTimeParams>,
     txn: &DatabaseTransaction,
     exec_plan: Arc<ExecutionPlan>,
     task_perf_aggregator: Arc<Mutex<TaskPerfAggregator>>,
-) -> Result<(), DbErr> {
-    let mut perf_aggregator = PerfAggregator::new();
-
+) -> Result<(usize, Duration), DbErr> {
     let block_parse_counter = std::time::Instant::now();
 
     let block_payload = hex::decode(cbor_hex.clone()).unwrap();
@@ -286,9 +631,10 @@ async fn insert_block(
         era: to_era_value(&multi_block),
         epoch,
         epoch_slot,
+        slot_time,
     };
 
-    perf_aggregator.block_parse += block_parse_counter.elapsed();
+    let block_parse = block_parse_counter.elapsed();
 
     match &multi_block {
         MultiEraBlock::Byron(_byron) => {
@@ -311,5 +657,5 @@ async fn insert_block(
         }
     }
 
-    Ok(())
+    Ok((multi_block.transaction_bodies().len(), block_parse))
 }
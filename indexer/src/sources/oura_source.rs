@@ -1,12 +1,13 @@
-use crate::SourceConfig;
+use crate::{CustomNetworkConfig, SourceConfig};
 use anyhow::anyhow;
 use dcspark_blockchain_source::cardano::Point;
 
 use std::{str::FromStr, sync::Arc, thread::JoinHandle};
+use tokio::sync::mpsc;
 
 use crate::common::CardanoEventType;
 use crate::types::StoppableService;
-use oura::model::EventData;
+use oura::model::{Event, EventData};
 use oura::pipelining::SourceProvider;
 use oura::{
     filters::selection::{self, Predicate},
@@ -18,7 +19,9 @@ use oura::{
 
 pub struct OuraSource {
     _handles: Vec<JoinHandle<()>>,
-    input: StageReceiver,
+    // oura events are received on a blocking channel, so they are forwarded by a dedicated thread
+    // to make pulling them async (and cancellable)
+    input: mpsc::Receiver<Event>,
 
     // cardano-node always triggers a rollback event when you connect to it
     // if all the intersection points existed, if will return the most recent point you gave it
@@ -30,6 +33,7 @@ impl OuraSource {
     pub fn new(
         config: SourceConfig,
         network: String,
+        custom_network: Option<CustomNetworkConfig>,
         start_from: Vec<Point>,
     ) -> anyhow::Result<Self> {
         match config {
@@ -68,7 +72,12 @@ impl OuraSource {
                     }
                 };
 
-                let (handles, input) = oura_bootstrap(bearer, intersect, &network, socket)?;
+                let (mut handles, oura_input) =
+                    oura_bootstrap(bearer, intersect, &network, custom_network.as_ref(), socket)?;
+                let (sender, input) = mpsc::channel(1);
+                handles.push(std::thread::spawn(move || {
+                    forward_events(oura_input, sender)
+                }));
 
                 Ok(OuraSource {
                     _handles: handles,
@@ -94,7 +103,8 @@ impl dcspark_blockchain_source::Source for OuraSource {
         let input = self
             .input
             .recv()
-            .map_err(|error| anyhow!("Can't fetch oura event: {:?}", error))?;
+            .await
+            .ok_or_else(|| anyhow!("Can't fetch oura event: the oura pipeline stopped"))?;
 
         match input.data {
             EventData::Block(block_record) => {
@@ -137,18 +147,48 @@ impl StoppableService for OuraSource {
     }
 }
 
+/// Stops once oura stops or once the source is dropped
+fn forward_events(oura_input: StageReceiver, sender: mpsc::Sender<Event>) {
+    while let Ok(event) = oura_input.recv() {
+        if sender.blocking_send(event).is_err() {
+            break;
+        }
+    }
+}
+
 fn oura_bootstrap(
     mode: BearerKind,
     intersect: IntersectArg,
     network: &str,
+    custom_network: Option<&CustomNetworkConfig>,
     socket: String,
 ) -> anyhow::Result<(Vec<JoinHandle<()>>, StageReceiver)> {
-    let magic = match network {
-        "sanchonet" => MagicArg(4),
-        _ => MagicArg::from_str(network).map_err(|_| anyhow!("magic arg failed"))?,
+    let magic = match (network, custom_network) {
+        (_, Some(custom_network)) => MagicArg(custom_network.magic as u64),
+        ("sanchonet", None) => MagicArg(4),
+        (_, None) => MagicArg::from_str(network).map_err(|_| anyhow!("magic arg failed"))?,
     };
 
-    let well_known = if magic.0 == 4 {
+    let well_known = if let Some(custom_network) = custom_network {
+        let shelley_start_slot = custom_network.shelley_start_slot();
+        ChainWellKnownInfo {
+            byron_epoch_length: (custom_network.byron_epoch_length
+                * custom_network.byron_slot_length) as u32,
+            byron_slot_length: custom_network.byron_slot_length as u32,
+            byron_known_slot: 0,
+            byron_known_hash: "".to_string(),
+            byron_known_time: custom_network.system_start,
+            shelley_epoch_length: (custom_network.shelley_epoch_length
+                * custom_network.shelley_slot_length) as u32,
+            shelley_slot_length: custom_network.shelley_slot_length as u32,
+            shelley_known_slot: shelley_start_slot,
+            shelley_known_hash: "".to_string(),
+            shelley_known_time: custom_network.system_start
+                + shelley_start_slot * custom_network.byron_slot_length,
+            address_hrp: custom_network.address_hrp().to_string(),
+            adahandle_policy: "".to_string(),
+        }
+    } else if magic.0 == 4 {
         ChainWellKnownInfo {
             byron_epoch_length: 86400,
             byron_slot_length: 20,
@@ -0,0 +1,10 @@
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct DexCandleConfig {
+    /// length of the candles to keep up to date, in seconds
+    #[serde(default = "default_intervals")]
+    pub intervals: Vec<u64>,
+}
+
+fn default_intervals() -> Vec<u64> {
+    vec![60, 60 * 60, 24 * 60 * 60]
+}
@@ -0,0 +1,18 @@
+/// Scripts used to find the pools and orders of a dex
+/// Every field is optional and defaults to the mainnet value for the dex of the task
+/// note: the task fails if a field can't be parsed (ex: a script hash that isn't 28 bytes long)
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct DexConfig {
+    /// hex-encoded payment script hashes of the pool outputs
+    #[serde(default)]
+    pub pool_script_hashes: Option<Vec<String>>,
+    /// hex-encoded payment script hashes of the order (or request) outputs
+    #[serde(default)]
+    pub order_script_hashes: Option<Vec<String>>,
+    /// bech32 addresses of the order outputs, for dexes that identify orders by address
+    #[serde(default)]
+    pub order_addresses: Option<Vec<String>>,
+    /// hex-encoded policy id of the pool NFTs, which also mints the LP tokens
+    #[serde(default)]
+    pub pool_nft_policy_id: Option<String>,
+}
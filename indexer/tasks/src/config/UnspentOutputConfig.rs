@@ -0,0 +1,12 @@
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct UnspentOutputConfig {
+    /// number of blocks after which spent outputs are removed
+    /// they can't be restored by a rollback deeper than this (mainnet k: 2160)
+    /// so it can't be lower than the `max_rollback_depth` of the sink
+    #[serde(default = "default_prune_depth")]
+    pub prune_depth: u64,
+}
+
+fn default_prune_depth() -> u64 {
+    2160
+}
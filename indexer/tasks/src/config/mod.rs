@@ -1,4 +1,8 @@
 #[allow(non_snake_case)]
+pub mod DexCandleConfig;
+#[allow(non_snake_case)]
+pub mod DexConfig;
+#[allow(non_snake_case)]
 pub mod EmptyConfig;
 #[allow(non_snake_case)]
 pub mod PayloadAndReadonlyConfig;
@@ -8,3 +12,5 @@ pub mod PayloadConfig;
 pub mod ReadonlyConfig;
 #[allow(non_snake_case)]
 pub mod ScriptHashConfig;
+#[allow(non_snake_case)]
+pub mod UnspentOutputConfig;
@@ -1,5 +1,5 @@
-use crate::utils::TaskPerfAggregator;
-use cml_chain::genesis::byron::config::GenesisData;
+use crate::genesis::CardanoGenesis;
+use crate::utils::{SlotTimeParams, TaskPerfAggregator};
 use entity::{block::EraValue, prelude::*, sea_orm::DatabaseTransaction};
 use shred::DispatcherBuilder;
 use std::sync::{Arc, Mutex};
@@ -9,6 +9,8 @@ pub struct BlockGlobalInfo {
     pub era: EraValue,
     pub epoch: Option<u64>,
     pub epoch_slot: Option<u64>,
+    /// converts slots to unix time (None if the slot parameters of the network are unknown)
+    pub slot_time: Option<SlotTimeParams>,
 }
 
 pub type BlockInfo<'a, BlockType, BlockExtraType> = (
@@ -19,7 +21,12 @@ pub type BlockInfo<'a, BlockType, BlockExtraType> = (
 
 pub trait DatabaseTaskMeta<'a, BlockType, BlockExtraType, Configuration> {
     const TASK_NAME: &'static str;
+    /// Bumped when a change to the task affects the data it writes,
+    /// so that the blocks indexed by each version can be told apart (see `TaskBlockRange`)
+    const VERSION: i32;
     const DEPENDENCIES: &'static [&'static str];
+    /// Tasks that have to run before this one only if they are part of the execution plan
+    const OPTIONAL_DEPENDENCIES: &'static [&'static str];
 
     fn new(
         db_tx: &'a DatabaseTransaction,
@@ -39,7 +46,15 @@ pub trait DatabaseTaskMeta<'a, BlockType, BlockExtraType, Configuration> {
 
 pub trait TaskBuilder<'a, BlockType, BlockExtraType> {
     fn get_name(&self) -> &'static str;
+    fn get_version(&self) -> i32;
     fn get_dependencies(&self) -> &'static [&'static str];
+    fn get_optional_dependencies(&self) -> &'static [&'static str];
+
+    /// Checks the properties of the task in the execution plan match its configuration type
+    fn validate_configuration(
+        &self,
+        properties: &toml::value::Value,
+    ) -> Result<(), toml::de::Error>;
 
     fn maybe_add_task<'c>(
         &self,
@@ -59,9 +74,62 @@ pub enum TaskRegistryEntry {
     Multiera(MultieraTaskRegistryEntry),
 }
 
+impl TaskRegistryEntry {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            TaskRegistryEntry::Genesis(entry) => entry.builder.get_name(),
+            TaskRegistryEntry::Byron(entry) => entry.builder.get_name(),
+            TaskRegistryEntry::Multiera(entry) => entry.builder.get_name(),
+        }
+    }
+
+    pub fn get_version(&self) -> i32 {
+        match self {
+            TaskRegistryEntry::Genesis(entry) => entry.builder.get_version(),
+            TaskRegistryEntry::Byron(entry) => entry.builder.get_version(),
+            TaskRegistryEntry::Multiera(entry) => entry.builder.get_version(),
+        }
+    }
+
+    pub fn get_dependencies(&self) -> &'static [&'static str] {
+        match self {
+            TaskRegistryEntry::Genesis(entry) => entry.builder.get_dependencies(),
+            TaskRegistryEntry::Byron(entry) => entry.builder.get_dependencies(),
+            TaskRegistryEntry::Multiera(entry) => entry.builder.get_dependencies(),
+        }
+    }
+
+    pub fn get_optional_dependencies(&self) -> &'static [&'static str] {
+        match self {
+            TaskRegistryEntry::Genesis(entry) => entry.builder.get_optional_dependencies(),
+            TaskRegistryEntry::Byron(entry) => entry.builder.get_optional_dependencies(),
+            TaskRegistryEntry::Multiera(entry) => entry.builder.get_optional_dependencies(),
+        }
+    }
+
+    pub fn get_era(&self) -> &'static str {
+        match self {
+            TaskRegistryEntry::Genesis(_) => "genesis",
+            TaskRegistryEntry::Byron(_) => "byron",
+            TaskRegistryEntry::Multiera(_) => "multiera",
+        }
+    }
+
+    pub fn validate_configuration(
+        &self,
+        properties: &toml::value::Value,
+    ) -> Result<(), toml::de::Error> {
+        match self {
+            TaskRegistryEntry::Genesis(entry) => entry.builder.validate_configuration(properties),
+            TaskRegistryEntry::Byron(entry) => entry.builder.validate_configuration(properties),
+            TaskRegistryEntry::Multiera(entry) => entry.builder.validate_configuration(properties),
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct GenesisTaskRegistryEntry {
-    pub builder: &'static (dyn for<'a> TaskBuilder<'a, GenesisData, BlockGlobalInfo> + Sync),
+    pub builder: &'static (dyn for<'a> TaskBuilder<'a, CardanoGenesis, BlockGlobalInfo> + Sync),
 }
 
 #[derive(Copy, Clone)]
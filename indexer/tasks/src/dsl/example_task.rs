@@ -10,9 +10,15 @@ carp_task! {
   doc "An example task to help people learn how to write custom Carp tasks";
   // The era your task operates on. Note: different eras have different block representations
   era multiera;
+  // (optional) Version of the task, 1 by default. Bump it when a change affects the data the task writes
+  // Note: the block ranges indexed by each version of a task are recorded in the TaskBlockRange table
+  version 1;
   // List of dependencies for this task. This is an array of names of other tasks
   // Note: your task will run if all dependencies either ran successfully OR were skipped for this block
   dependencies [];
+  // (optional) List of tasks that must run before this task only if they are part of the execution plan
+  // Note: unlike regular dependencies, they aren't required nor automatically added to the plan
+  optional_dependencies [];
   // Specify which fields your task will have read-access to
   read [multiera_txs];
   // Specify which fields your task will have write-access to
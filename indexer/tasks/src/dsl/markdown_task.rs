@@ -16,6 +16,7 @@ pub trait MarkdownTaskMeta {
     const READ_FROM: &'static [&'static str];
     const WRITE_TO: &'static [&'static str];
     const DEPENDENCIES: &'static [&'static str];
+    const OPTIONAL_DEPENDENCIES: &'static [&'static str];
 }
 
 pub trait TaskMarkdownBuilder {
@@ -25,6 +26,7 @@ pub trait TaskMarkdownBuilder {
     fn get_reads(&self) -> &'static [&'static str];
     fn get_writes(&self) -> &'static [&'static str];
     fn get_dependencies(&self) -> &'static [&'static str];
+    fn get_optional_dependencies(&self) -> &'static [&'static str];
 
     fn generate_docs(&self, file: &mut Markdown<File>);
 }
@@ -1,4 +1,5 @@
 pub(crate) use super::execution_context::*;
+pub use crate::genesis::CardanoGenesis;
 pub use crate::utils::find_task_registry_entry;
 pub use crate::{
     dsl::database_task::{
@@ -15,7 +16,7 @@ pub use std::sync::{Arc, Mutex};
 
 macro_rules! era_to_block {
     (genesis) => {
-        GenesisData
+        CardanoGenesis
     };
     (byron) => {
         cml_multi_era::MultiEraBlock
@@ -37,6 +38,16 @@ macro_rules! era_to_block_info {
     };
 }
 
+/// Tasks that don't specify a version are at version 1
+macro_rules! task_version {
+    () => {
+        1
+    };
+    ($version:literal) => {
+        $version
+    };
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "build_markdown_task")] {
         macro_rules! era_to_registry {
@@ -63,7 +74,9 @@ cfg_if::cfg_if! {
                 configuration $config:ty;
                 doc $doc:expr;
                 era $era:ident;
+                $( version $version:literal; )?
                 dependencies [ $( $dep:ty ),* ];
+                $( optional_dependencies [ $( $optional_dep:ty ),* ]; )?
                 read [ $( $read_name:ident ),* ];
                 write [ $( $write_name:ident ),* ];
                 should_add_task |$block:ident, $properties:ident| { $($should_add_task:tt)* };
@@ -100,6 +113,11 @@ cfg_if::cfg_if! {
                             nameof::name_of_type!($dep)
                         ),*
                     ];
+                    const OPTIONAL_DEPENDENCIES: &'static [&'static str] = &[
+                        $($(
+                            nameof::name_of_type!($optional_dep)
+                        ),*)?
+                    ];
                 }
 
                 paste! { struct [< $name Builder >]; }
@@ -122,6 +140,9 @@ cfg_if::cfg_if! {
                     fn get_dependencies(&self) -> &'static [&'static str] {
                         $name::DEPENDENCIES
                     }
+                    fn get_optional_dependencies(&self) -> &'static [&'static str] {
+                        $name::OPTIONAL_DEPENDENCIES
+                    }
 
                     fn generate_docs(
                         &self,
@@ -158,6 +179,19 @@ cfg_if::cfg_if! {
                             md.write("\n").unwrap();
                         }
 
+                        let optional_dependencies = self.get_optional_dependencies();
+                        let optional_dep_strs: Vec<String> = optional_dependencies.iter().map(|dep| format!("./{}", encode(dep))).collect();
+                        if !optional_dependencies.is_empty() {
+                            md.write("Optional dependencies".heading(2)).unwrap();
+                            md.write("Run before this task when they are part of the execution plan".paragraph()).unwrap();
+                            let mut dep_list = List::new(false);
+                            for (i, dep) in optional_dependencies.iter().enumerate() {
+                                dep_list = dep_list.item(dep.link_to(&optional_dep_strs[i]));
+                            }
+                            md.write_raw(dep_list).unwrap();
+                            md.write("\n").unwrap();
+                        }
+
                         md.write("Data accessed".heading(2)).unwrap();
 
                         let reads = self.get_reads();
@@ -222,7 +256,9 @@ cfg_if::cfg_if! {
               configuration $config:ty;
               doc $doc:expr;
               era $era:ident;
+              $( version $version:literal; )?
               dependencies [ $( $dep:ty ),* ];
+              $( optional_dependencies [ $( $optional_dep:ty ),* ]; )?
               read [ $( $read_name:ident ),* ];
               write [ $( $write_name:ident ),* ];
               should_add_task |$block:ident, $properties:ident| { $($should_add_task:tt)* };
@@ -249,11 +285,17 @@ cfg_if::cfg_if! {
 
                 impl<'a> DatabaseTaskMeta<'a, era_to_block!($era), era_to_block_info!($era), $config> for $name<'a> {
                     const TASK_NAME: &'static str = stringify!($name);
+                    const VERSION: i32 = task_version!($($version)?);
                     const DEPENDENCIES: &'static [&'static str] = &[
                         $(
                             nameof::name_of_type!($dep)
                         ),*
                     ];
+                    const OPTIONAL_DEPENDENCIES: &'static [&'static str] = &[
+                        $($(
+                            nameof::name_of_type!($optional_dep)
+                        ),*)?
+                    ];
 
                     fn new(
                         db_tx: &'a DatabaseTransaction,
@@ -288,9 +330,22 @@ cfg_if::cfg_if! {
                     fn get_name(&self) -> &'static str {
                         $name::TASK_NAME
                     }
+                    fn get_version(&self) -> i32 {
+                        $name::VERSION
+                    }
                     fn get_dependencies(&self) -> &'static [&'static str] {
                         $name::DEPENDENCIES
                     }
+                    fn get_optional_dependencies(&self) -> &'static [&'static str] {
+                        $name::OPTIONAL_DEPENDENCIES
+                    }
+
+                    fn validate_configuration(
+                        &self,
+                        configuration: &toml::value::Value,
+                    ) -> Result<(), toml::de::Error> {
+                        configuration.clone().try_into::<$config>().map(|_| ())
+                    }
 
                     fn maybe_add_task<'c>(
                         &self,
@@ -308,13 +363,14 @@ cfg_if::cfg_if! {
                           let task = $name::new(db_tx, block, handle, perf_aggregator, &config);
 
                           // 1) Check that all dependencies are registered tasks
-                          for dep in self.get_dependencies().iter() {
+                          for dep in self.get_dependencies().iter().chain(self.get_optional_dependencies()) {
                             if find_task_registry_entry(dep).is_none() {
                                 panic!("Could not find task named {} in dependencies of {}", dep, self.get_name());
                             }
                           }
-                          // 2) Filter out any dependency that got skipped
+                          // 2) Filter out any dependency that got skipped (or isn't part of the plan)
                           let filtered_deps: Vec<&str> = self.get_dependencies().iter()
+                            .chain(self.get_optional_dependencies())
                             .map(|&dep| dep)
                             .filter(|&dep| dispatcher_builder.has_system(dep))
                             .collect();
@@ -363,3 +419,4 @@ pub(crate) use carp_task;
 pub(crate) use era_to_block;
 pub(crate) use era_to_block_info;
 pub(crate) use era_to_registry;
+pub(crate) use task_version;
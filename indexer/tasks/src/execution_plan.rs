@@ -1,19 +1,37 @@
+use std::collections::BTreeSet;
+use std::fmt;
 use std::fs;
 
 use anyhow::anyhow;
 use toml::Value;
 use tracing_subscriber::prelude::*;
 
+use crate::utils::find_task_registry_entry;
+
 pub struct ExecutionPlan(pub toml::value::Table);
 
 impl ExecutionPlan {
-    pub fn load_from_file(path: &str) -> anyhow::Result<ExecutionPlan> {
+    /// Loads the execution plan, sorts its tasks (see `sort_tasks`) and checks it's valid
+    /// (see `validate`)
+    pub fn load_from_file(
+        path: &str,
+        include_missing_dependencies: bool,
+    ) -> anyhow::Result<ExecutionPlan> {
+        let mut plan = ExecutionPlan::load_from_file_unchecked(path)?;
+        plan.sort_tasks(include_missing_dependencies)
+            .and_then(|()| plan.validate())
+            .map_err(|errors| anyhow!("Invalid execution plan {}:\n{}", path, errors))?;
+        Ok(plan)
+    }
+
+    /// Loads the execution plan without checking the tasks it contains
+    pub fn load_from_file_unchecked(path: &str) -> anyhow::Result<ExecutionPlan> {
         match &fs::read_to_string(path) {
             Ok(execution_plan_content) => {
-                let setting: Result<toml::value::Table, toml::de::Error> =
-                    toml::from_str(execution_plan_content);
+                let setting: toml::value::Table = toml::from_str(execution_plan_content)
+                    .map_err(|err| anyhow!("Execution plan {} isn't valid toml: {}", path, err))?;
 
-                Ok(ExecutionPlan(setting.unwrap()))
+                Ok(ExecutionPlan(setting))
             }
             Err(err) => {
                 tracing::error!("No execution plan found at {}", path);
@@ -25,4 +43,300 @@ impl ExecutionPlan {
             }
         }
     }
+
+    /// Parses a plan previously serialized with `to_toml`. The plan isn't sorted nor validated
+    pub fn from_toml(content: &str) -> anyhow::Result<ExecutionPlan> {
+        let setting: toml::value::Table = toml::from_str(content)
+            .map_err(|err| anyhow!("Execution plan isn't valid toml: {}", err))?;
+        Ok(ExecutionPlan(setting))
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(&self.0).unwrap()
+    }
+
+    /// Human-readable list of the tasks added, removed or reconfigured compared to another plan
+    pub fn changes_since(&self, previous: &ExecutionPlan) -> Vec<String> {
+        let mut changes = vec![];
+        for (task_name, val) in self.0.iter() {
+            match previous.0.get(task_name) {
+                None => changes.push(format!("{task_name} was added")),
+                Some(previous_val) if previous_val != val => {
+                    changes.push(format!("{task_name} configuration changed"))
+                }
+                Some(_) => (),
+            }
+        }
+        for task_name in previous.0.keys() {
+            if !self.0.contains_key(task_name) {
+                changes.push(format!("{task_name} was removed"));
+            }
+        }
+        changes
+    }
+
+    /// Reorders the tasks of the plan so that every task is placed after its dependencies.
+    /// Tasks keep the order they were specified in when they don't depend on each other.
+    /// If `include_missing_dependencies` is set, dependencies missing from the plan (and their own
+    /// dependencies) are added with an empty configuration. Optional dependencies are never added
+    pub fn sort_tasks(
+        &mut self,
+        include_missing_dependencies: bool,
+    ) -> Result<(), PlanValidationErrors> {
+        if include_missing_dependencies {
+            let mut to_check: Vec<String> = self.0.keys().cloned().collect();
+            while let Some(task_name) = to_check.pop() {
+                let entry = match find_task_registry_entry(&task_name) {
+                    Some(entry) => entry,
+                    None => continue,
+                };
+                for &dependency in entry.get_dependencies() {
+                    if self.0.contains_key(dependency)
+                        || find_task_registry_entry(dependency).is_none()
+                    {
+                        continue;
+                    }
+                    tracing::info!(
+                        "Adding {} to the execution plan as a dependency of {}",
+                        dependency,
+                        task_name
+                    );
+                    self.0.insert(
+                        dependency.to_string(),
+                        Value::Table(toml::value::Table::new()),
+                    );
+                    to_check.push(dependency.to_string());
+                }
+            }
+        }
+
+        let plan_dependencies = |task_name: &str| -> Vec<&'static str> {
+            match find_task_registry_entry(task_name) {
+                // note: dependencies missing from the plan are reported by `validate`
+                Some(entry) => entry
+                    .get_dependencies()
+                    .iter()
+                    .chain(entry.get_optional_dependencies())
+                    .copied()
+                    .filter(|dependency| self.0.contains_key(*dependency))
+                    .collect(),
+                None => vec![],
+            }
+        };
+
+        let mut remaining: Vec<String> = self.0.keys().cloned().collect();
+        let mut sorted: Vec<String> = Vec::with_capacity(remaining.len());
+        let mut sorted_set = BTreeSet::<String>::new();
+        while let Some(next) = remaining.iter().position(|task_name| {
+            plan_dependencies(task_name)
+                .iter()
+                .all(|dependency| sorted_set.contains(*dependency))
+        }) {
+            let task_name = remaining.remove(next);
+            sorted_set.insert(task_name.clone());
+            sorted.push(task_name);
+        }
+
+        if let Some(first_remaining) = remaining.first() {
+            // every remaining task depends on another remaining task so following the dependencies
+            // of any of them eventually loops back
+            let mut cycle = vec![first_remaining.clone()];
+            loop {
+                let current = cycle.last().unwrap();
+                let next = plan_dependencies(current)
+                    .into_iter()
+                    .find(|dependency| remaining.iter().any(|task| task == *dependency))
+                    .unwrap()
+                    .to_string();
+                if let Some(cycle_start) = cycle.iter().position(|task| *task == next) {
+                    cycle.drain(..cycle_start);
+                    cycle.push(next);
+                    break;
+                }
+                cycle.push(next);
+            }
+            return Err(PlanValidationErrors(vec![
+                PlanValidationError::DependencyCycle { tasks: cycle },
+            ]));
+        }
+
+        let mut sorted_table = toml::value::Table::new();
+        for task_name in sorted {
+            let val = self.0.remove(&task_name).unwrap();
+            sorted_table.insert(task_name, val);
+        }
+        self.0 = sorted_table;
+
+        Ok(())
+    }
+
+    /// Checks that:
+    /// 1) every task of the plan is a registered task
+    /// 2) the dependencies of every task are part of the plan, in the same era and placed before it
+    ///    (optional dependencies only have to be in the same era and placed before it if they are part of the plan)
+    /// 3) the properties of every task deserialize into the configuration type of the task
+    pub fn validate(&self) -> Result<(), PlanValidationErrors> {
+        let mut errors = vec![];
+
+        for (index, (task_name, val)) in self.0.iter().enumerate() {
+            if !val.is_table() {
+                errors.push(PlanValidationError::NotATable {
+                    task: task_name.clone(),
+                });
+                continue;
+            }
+            let entry = match find_task_registry_entry(task_name) {
+                Some(entry) => entry,
+                None => {
+                    errors.push(PlanValidationError::UnknownTask {
+                        task: task_name.clone(),
+                    });
+                    continue;
+                }
+            };
+
+            let optional_dependencies = entry.get_optional_dependencies();
+            for &dependency in entry.get_dependencies().iter().chain(optional_dependencies) {
+                let dependency_entry = match find_task_registry_entry(dependency) {
+                    Some(dependency_entry) => dependency_entry,
+                    None => {
+                        errors.push(PlanValidationError::UnknownDependency {
+                            task: task_name.clone(),
+                            dependency: dependency.to_string(),
+                        });
+                        continue;
+                    }
+                };
+                if dependency_entry.get_era() != entry.get_era() {
+                    errors.push(PlanValidationError::DependencyEraMismatch {
+                        task: task_name.clone(),
+                        dependency: dependency.to_string(),
+                    });
+                    continue;
+                }
+                match self.0.keys().position(|name| name == dependency) {
+                    None if optional_dependencies.contains(&dependency) => (),
+                    None => errors.push(PlanValidationError::MissingDependency {
+                        task: task_name.clone(),
+                        dependency: dependency.to_string(),
+                    }),
+                    Some(dependency_index) if dependency_index > index => {
+                        errors.push(PlanValidationError::DependencyOutOfOrder {
+                            task: task_name.clone(),
+                            dependency: dependency.to_string(),
+                        })
+                    }
+                    Some(_) => (),
+                }
+            }
+
+            if let Err(error) = entry.validate_configuration(val) {
+                errors.push(PlanValidationError::InvalidConfiguration {
+                    task: task_name.clone(),
+                    error,
+                });
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(PlanValidationErrors(errors)),
+        }
+    }
 }
+
+#[derive(Debug)]
+pub enum PlanValidationError {
+    /// Entries of the plan have to be tables (ex: `[MultieraBlockTask]`)
+    NotATable {
+        task: String,
+    },
+    UnknownTask {
+        task: String,
+    },
+    /// A task depends on a task that isn't registered (bug in the task definition)
+    UnknownDependency {
+        task: String,
+        dependency: String,
+    },
+    DependencyEraMismatch {
+        task: String,
+        dependency: String,
+    },
+    MissingDependency {
+        task: String,
+        dependency: String,
+    },
+    DependencyOutOfOrder {
+        task: String,
+        dependency: String,
+    },
+    /// Tasks depending on each other, the first task is repeated at the end of the list
+    DependencyCycle {
+        tasks: Vec<String>,
+    },
+    InvalidConfiguration {
+        task: String,
+        error: toml::de::Error,
+    },
+}
+
+impl fmt::Display for PlanValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanValidationError::NotATable { task } => {
+                write!(f, "{task}: expected a table (ex: [{task}])")
+            }
+            PlanValidationError::UnknownTask { task } => {
+                write!(f, "{task}: no task with this name exists")
+            }
+            PlanValidationError::UnknownDependency { task, dependency } => {
+                write!(
+                    f,
+                    "{task}: depends on {dependency} which isn't a registered task"
+                )
+            }
+            PlanValidationError::DependencyEraMismatch { task, dependency } => {
+                write!(
+                    f,
+                    "{task}: depends on {dependency} which doesn't run in the same era"
+                )
+            }
+            PlanValidationError::MissingDependency { task, dependency } => {
+                write!(
+                    f,
+                    "{task}: depends on {dependency} which isn't in the execution plan"
+                )
+            }
+            PlanValidationError::DependencyOutOfOrder { task, dependency } => {
+                write!(
+                    f,
+                    "{task}: depends on {dependency} which has to be placed before it in the plan"
+                )
+            }
+            PlanValidationError::DependencyCycle { tasks } => {
+                write!(f, "dependency cycle: {}", tasks.join(" -> "))
+            }
+            PlanValidationError::InvalidConfiguration { task, error } => {
+                write!(f, "{task}: invalid configuration: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlanValidationError {}
+
+/// All the problems found in an execution plan
+#[derive(Debug)]
+pub struct PlanValidationErrors(pub Vec<PlanValidationError>);
+
+impl fmt::Display for PlanValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for error in self.0.iter() {
+            writeln!(f, "  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PlanValidationErrors {}
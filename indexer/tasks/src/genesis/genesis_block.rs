@@ -28,10 +28,10 @@ carp_task! {
 
 async fn handle_block(
     db_tx: &DatabaseTransaction,
-    block: BlockInfo<'_, GenesisData, BlockGlobalInfo>,
+    block: BlockInfo<'_, CardanoGenesis, BlockGlobalInfo>,
     include_payload: bool,
 ) -> Result<BlockModel, DbErr> {
-    let genesis_hash = block.1.genesis_prev.to_raw_bytes().to_vec();
+    let genesis_hash = block.1.byron.genesis_prev.to_raw_bytes().to_vec();
 
     let block_payload = if include_payload {
         hex::decode(block.0).unwrap()
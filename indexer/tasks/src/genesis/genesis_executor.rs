@@ -3,16 +3,16 @@ use std::sync::{Arc, Mutex};
 use crate::dsl::database_task::TaskRegistryEntry;
 use crate::dsl::database_task::{BlockGlobalInfo, BlockInfo};
 use crate::execution_plan::ExecutionPlan;
+use crate::genesis::CardanoGenesis;
 use crate::utils::find_task_registry_entry;
 use crate::utils::TaskPerfAggregator;
-use cml_chain::genesis::byron::config::GenesisData;
 use entity::sea_orm::{prelude::*, DatabaseTransaction};
 use shred::{DispatcherBuilder, World};
 use tokio::runtime::Handle;
 
 pub async fn process_genesis_block(
     txn: &DatabaseTransaction,
-    block: BlockInfo<'_, GenesisData, BlockGlobalInfo>,
+    block: BlockInfo<'_, CardanoGenesis, BlockGlobalInfo>,
     exec_plan: &ExecutionPlan,
     perf_aggregator: Arc<Mutex<TaskPerfAggregator>>,
 ) -> Result<(), DbErr> {
@@ -24,6 +24,8 @@ pub async fn process_genesis_block(
 
     let mut dispatcher_builder = DispatcherBuilder::new();
 
+    // note: tasks are sorted by dependencies when the plan is loaded
+    // so this iterates over them in an order the dispatcher accepts
     for (task_name, val) in exec_plan.0.iter() {
         if let toml::value::Value::Table(_task_props) = val {
             let entry = find_task_registry_entry(task_name);
@@ -0,0 +1,107 @@
+use crate::config::EmptyConfig::EmptyConfig;
+use cml_chain::certs::StakeCredential;
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use entity::{
+    prelude::*,
+    sea_orm::{ActiveModelTrait, DatabaseTransaction, DbErr, EntityTrait, Set},
+};
+
+use crate::dsl::task_macro::*;
+use crate::multiera::multiera_pool_registration::pool_registration_model;
+
+use super::genesis_shelley_txs::GenesisShelleyTransactionTask;
+
+carp_task! {
+  name GenesisShelleyStakingTask;
+  configuration EmptyConfig;
+  doc "Parses the initial stake pools and stake delegations of the Shelley genesis";
+  era genesis;
+  dependencies [GenesisShelleyTransactionTask];
+  read [genesis_block, genesis_txs];
+  write [];
+  should_add_task |block, _properties| {
+    block
+      .1
+      .shelley
+      .as_ref()
+      .and_then(|shelley| shelley.data.staking.as_ref())
+      .map(|staking| !staking.stake.is_empty() || !staking.pools.is_empty())
+      .unwrap_or(false)
+  };
+  execute |previous_data, task| handle_staking(
+      task.db_tx,
+      task.block,
+      previous_data.genesis_block.as_ref().unwrap(),
+      previous_data.genesis_txs.len(),
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle_staking(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, CardanoGenesis, BlockGlobalInfo>,
+    database_block: &BlockModel,
+    tx_index: usize,
+) -> Result<(), DbErr> {
+    let genesis = block.1.shelley.as_ref().unwrap();
+    let staking = genesis.data.staking.as_ref().unwrap();
+
+    // the genesis staking isn't part of any transaction
+    // so we attach it to a transaction identified by the hash of the genesis file
+    let staking_tx = TransactionActiveModel {
+        block_id: Set(database_block.id),
+        hash: Set(genesis.hash.clone()),
+        is_valid: Set(true),
+        payload: Set(vec![]),
+        tx_index: Set(tx_index as i32),
+        ..Default::default()
+    }
+    .insert(db_tx)
+    .await?;
+
+    if !staking.pools.is_empty() {
+        PoolRegistration::insert_many(
+            staking
+                .pools
+                .values()
+                .map(|params| pool_registration_model(params, staking_tx.id)),
+        )
+        .exec(db_tx)
+        .await?;
+    }
+
+    if staking.stake.is_empty() {
+        return Ok(());
+    }
+
+    // note: StakeCredential here is the cml type, not the entity
+    let inserted_credentials =
+        entity::stake_credential::Entity::insert_many(staking.stake.keys().map(|key_hash| {
+            StakeCredentialActiveModel {
+                credential: Set(StakeCredential::new_pub_key(*key_hash).to_cbor_bytes()),
+                first_tx: Set(staking_tx.id),
+                ..Default::default()
+            }
+        }))
+        .exec_many_with_returning(db_tx)
+        .await?;
+
+    entity::stake_delegation::Entity::insert_many(
+        staking
+            .stake
+            .values()
+            .zip(&inserted_credentials)
+            .map(|(pool, credential)| entity::stake_delegation::ActiveModel {
+                stake_credential: Set(credential.id),
+                pool_credential: Set(Some(pool.to_raw_bytes().to_vec())),
+                tx_id: Set(staking_tx.id),
+                previous_pool: Set(None),
+                ..Default::default()
+            }),
+    )
+    .exec(db_tx)
+    .await?;
+
+    Ok(())
+}
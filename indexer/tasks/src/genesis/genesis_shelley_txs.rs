@@ -0,0 +1,126 @@
+use crate::config::PayloadConfig::PayloadConfig;
+use cml_chain::assets::Value;
+use cml_core::serialization::Serialize;
+use entity::{
+    prelude::*,
+    sea_orm::{DatabaseTransaction, DbErr, EntityTrait, Set},
+};
+
+use crate::dsl::task_macro::*;
+use crate::utils::blake2b256;
+
+use super::genesis_txs::{bulk_insert_txs, GenesisTransactionTask};
+
+carp_task! {
+  name GenesisShelleyTransactionTask;
+  configuration PayloadConfig;
+  doc "Parses the initial funds of the Shelley genesis";
+  era genesis;
+  dependencies [GenesisTransactionTask];
+  read [genesis_block];
+  write [genesis_txs, genesis_addresses, genesis_outputs];
+  should_add_task |block, _properties| {
+    block
+      .1
+      .shelley
+      .as_ref()
+      .map(|shelley| !shelley.data.initial_funds.is_empty())
+      .unwrap_or(false)
+  };
+  execute |previous_data, task| handle_txs(
+      task.db_tx,
+      task.block,
+      previous_data.genesis_block.as_ref().unwrap(),
+      previous_data.genesis_txs.len(),
+      task.config.include_payload
+  );
+  merge_result |previous_data, result| {
+    previous_data.genesis_txs.extend(result.0);
+    previous_data.genesis_addresses.extend(result.1);
+    previous_data.genesis_outputs.extend(result.2);
+  };
+}
+
+async fn handle_txs(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, CardanoGenesis, BlockGlobalInfo>,
+    database_block: &BlockModel,
+    // Byron genesis txs are inserted first
+    first_tx_index: usize,
+    include_payload: bool,
+) -> Result<
+    (
+        Vec<TransactionModel>,
+        Vec<AddressModel>,
+        Vec<TransactionOutputModel>,
+    ),
+    DbErr,
+> {
+    let genesis = &block.1.shelley.as_ref().unwrap().data;
+
+    let mut transactions: Vec<TransactionActiveModel> = vec![];
+    let mut addresses: Vec<Vec<u8>> = vec![];
+    let mut outputs: Vec<cml_chain::transaction::TransactionOutput> = vec![];
+
+    for (address, amount) in genesis.initial_funds.iter() {
+        let address_bytes = address.to_raw_bytes();
+        // like for Byron, the ledger uses the hash of the address as the tx hash of initial funds
+        // see initialFundsPseudoTxIn in cardano-ledger
+        let tx_hash = blake2b256(&address_bytes);
+        let payload = if include_payload {
+            address_bytes.clone()
+        } else {
+            vec![]
+        };
+
+        // note: strictly speaking, genesis txs are unordered so there is no defined index
+        let tx_index = (first_tx_index + transactions.len()) as i32;
+        transactions.push(TransactionActiveModel {
+            block_id: Set(database_block.id),
+            hash: Set(tx_hash.to_vec()),
+            is_valid: Set(true),
+            payload: Set(payload),
+            tx_index: Set(tx_index),
+            ..Default::default()
+        });
+
+        addresses.push(address_bytes);
+        outputs.push(cml_chain::transaction::TransactionOutput::new(
+            address.clone(),
+            Value::from(*amount),
+            None,
+            None,
+        ));
+    }
+
+    let inserted_txs = bulk_insert_txs(db_tx, &transactions).await?;
+    let inserted_addresses =
+        Address::insert_many(addresses.iter().zip(&inserted_txs).map(|(address, tx)| {
+            AddressActiveModel {
+                payload: Set(address.clone()),
+                first_tx: Set(tx.id),
+                ..Default::default()
+            }
+        }))
+        .exec_many_with_returning(db_tx)
+        .await?;
+
+    let outputs_to_add = inserted_txs
+        .iter()
+        .zip(&inserted_addresses)
+        .enumerate()
+        .map(|(i, (tx, addr))| TransactionOutputActiveModel {
+            address_id: Set(addr.id),
+            tx_id: Set(tx.id),
+            payload: Set(outputs[i].to_cbor_bytes()),
+            // recall: genesis txs are hashes of addresses
+            // so all txs have a single output
+            output_index: Set(0),
+            ..Default::default()
+        });
+    let inserted_outputs = TransactionOutput::insert_many(outputs_to_add)
+        .exec_many_with_returning(db_tx)
+        .await?;
+
+    Ok((inserted_txs, inserted_addresses, inserted_outputs))
+}
@@ -1,10 +1,7 @@
 extern crate shred;
 
 use crate::config::PayloadConfig::PayloadConfig;
-use cml_chain::{
-    byron::ByronAddress,
-    genesis::byron::{config::GenesisData, parse::redeem_pubkey_to_txid},
-};
+use cml_chain::{byron::ByronAddress, genesis::byron::parse::redeem_pubkey_to_txid};
 use cml_core::serialization::ToBytes;
 use cml_crypto::RawBytesEncoding;
 use entity::{
@@ -29,7 +26,7 @@ carp_task! {
   read [genesis_block];
   write [genesis_txs, genesis_addresses, genesis_outputs];
   should_add_task |block, _properties| {
-    !block.1.avvm_distr.is_empty() || !block.1.non_avvm_balances.is_empty()
+    !block.1.byron.avvm_distr.is_empty() || !block.1.byron.non_avvm_balances.is_empty()
   };
   execute |previous_data, task| handle_txs(
       task.db_tx,
@@ -46,7 +43,7 @@ carp_task! {
 
 async fn handle_txs(
     db_tx: &DatabaseTransaction,
-    block: BlockInfo<'_, GenesisData, BlockGlobalInfo>,
+    block: BlockInfo<'_, CardanoGenesis, BlockGlobalInfo>,
     database_block: &BlockModel,
     include_payload: bool,
 ) -> Result<
@@ -66,8 +63,9 @@ async fn handle_txs(
     let mut addresses: Vec<Box<dyn Fn(i64) -> AddressActiveModel>> = vec![];
     let mut outputs: Vec<cml_chain::byron::ByronTxOut> = vec![];
 
-    for (pub_key, amount) in block.1.avvm_distr.iter() {
-        let (tx_hash, byron_addr) = redeem_pubkey_to_txid(pub_key, Some(block.1.protocol_magic));
+    for (pub_key, amount) in block.1.byron.avvm_distr.iter() {
+        let (tx_hash, byron_addr) =
+            redeem_pubkey_to_txid(pub_key, Some(block.1.byron.protocol_magic));
         let payload = if include_payload {
             byron_addr.to_bytes()
         } else {
@@ -99,7 +97,7 @@ async fn handle_txs(
     }
 
     // note: empty on mainnet
-    for (byron_addr, amount) in block.1.non_avvm_balances.iter() {
+    for (byron_addr, amount) in block.1.byron.non_avvm_balances.iter() {
         let tx_hash = blake2b256(&byron_addr.to_bytes());
         let payload = if include_payload {
             byron_addr.to_bytes()
@@ -166,7 +164,7 @@ async fn handle_txs(
 }
 
 // https://github.com/SeaQL/sea-orm/issues/691
-async fn bulk_insert_txs(
+pub(crate) async fn bulk_insert_txs(
     txn: &DatabaseTransaction,
     transactions: &[TransactionActiveModel],
 ) -> Result<Vec<TransactionModel>, DbErr> {
@@ -1,3 +1,20 @@
+use cml_chain::genesis::{byron::config::GenesisData, shelley::config::ShelleyGenesisData};
+
 pub mod genesis_block;
 pub mod genesis_executor;
+pub mod genesis_shelley_staking;
+pub mod genesis_shelley_txs;
 pub mod genesis_txs;
+
+/// Content of the genesis files of a network
+pub struct CardanoGenesis {
+    pub byron: GenesisData,
+    /// None if no Shelley genesis file is available for the network
+    pub shelley: Option<ShelleyGenesis>,
+}
+
+pub struct ShelleyGenesis {
+    pub data: ShelleyGenesisData,
+    /// blake2b256 hash of the genesis file
+    pub hash: Vec<u8>,
+}
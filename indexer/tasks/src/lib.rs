@@ -7,5 +7,6 @@ pub mod era_common;
 pub mod execution_plan;
 pub mod genesis;
 pub mod multiera;
+pub mod task_ranges;
 pub mod types;
 pub mod utils;
@@ -1,38 +1,39 @@
+use super::{minswap_v1, minswap_v2, sundaeswap_v1, sundaeswap_v3, wingriders_v1, wingriders_v2};
+use crate::config::DexConfig::DexConfig;
 use crate::{dsl::database_task::BlockInfo, types::AssetPair};
 use crate::{
     dsl::task_macro::*,
-    multiera::utils::common::{
-        asset_from_pair, get_plutus_datum_for_output, get_shelley_payment_hash,
-    },
+    multiera::utils::common::{asset_from_pair, get_plutus_datum_for_output, output_from_bytes},
 };
+use cml_chain::certs::Credential;
 use cml_chain::json::plutus_datums::{
     decode_plutus_datum_to_json_str, decode_plutus_datum_to_json_value,
     CardanoNodePlutusDatumSchema,
 };
-use cml_chain::NonemptySetPlutusData;
+use cml_chain::{NonemptySetPlutusData, PolicyId};
+use cml_crypto::{RawBytesEncoding, ScriptHash};
+use entity::dex_liquidity::Operation as LiquidityOperation;
 use entity::dex_swap::Operation;
 use entity::sea_orm::{DatabaseTransaction, Set};
 use std::collections::{BTreeMap, BTreeSet};
 
-/// Returns an output and it's datum only if the output's payment hash is in `payment_hashes`
+/// Returns an output and it's datum only if the output's payment script hash is in `payment_hashes`
 /// and the plutus datum is known.
 pub fn filter_outputs_and_datums_by_hash(
     outputs: &[cml_multi_era::utils::MultiEraTransactionOutput],
-    payment_hashes: &[&str],
+    payment_hashes: &[ScriptHash],
     plutus_data: &Option<NonemptySetPlutusData>,
 ) -> Vec<(
     cml_multi_era::utils::MultiEraTransactionOutput,
     cml_chain::plutus::PlutusData,
 )> {
-    let payment_hashes = payment_hashes.iter().map(|&s| Some(s)).collect::<Vec<_>>();
     outputs
         .iter()
-        .filter_map(|o| {
-            if payment_hashes.contains(&get_shelley_payment_hash(o.address()).as_deref()) {
+        .filter_map(|o| match o.address().payment_cred() {
+            Some(Credential::Script { hash, .. }) if payment_hashes.contains(hash) => {
                 get_plutus_datum_for_output(o, plutus_data).map(|datum| (o.clone(), datum))
-            } else {
-                None
             }
+            _ => None,
         })
         .collect::<Vec<_>>()
 }
@@ -41,18 +42,16 @@ pub fn filter_outputs_and_datums_by_hash(
 /// and the plutus datum is known.
 pub fn filter_outputs_and_datums_by_address(
     outputs: &[cml_multi_era::utils::MultiEraTransactionOutput],
-    addresses: &[&str],
+    addresses: &[cml_chain::address::Address],
     plutus_data: &Option<NonemptySetPlutusData>,
 ) -> Vec<(
     cml_multi_era::utils::MultiEraTransactionOutput,
     cml_chain::plutus::PlutusData,
 )> {
-    let addresses = addresses.to_vec();
     outputs
         .iter()
         .filter_map(|o| {
-            let address_string = o.address().to_bech32(None).unwrap_or_default();
-            if addresses.contains(&address_string.as_str()) {
+            if addresses.contains(&o.address()) {
                 get_plutus_datum_for_output(o, plutus_data).map(|datum| (o.clone(), datum))
             } else {
                 None
@@ -61,6 +60,90 @@ pub fn filter_outputs_and_datums_by_address(
         .collect::<Vec<_>>()
 }
 
+/// Returns the outputs spent by the inputs of `tx` (in the order of the inputs)
+pub fn get_spent_outputs(
+    tx: &cml_multi_era::MultiEraTransactionBody,
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Result<Vec<cml_multi_era::utils::MultiEraTransactionOutput>, String> {
+    tx.inputs()
+        .iter()
+        .map(|input| {
+            let output = input
+                .hash()
+                .zip(input.index())
+                .and_then(|(hash, index)| {
+                    multiera_used_inputs_to_outputs_map
+                        .get(&hash.to_raw_bytes().to_vec())?
+                        .get(&(index as i64))
+                })
+                .ok_or("Failed to find spent output")?;
+            output_from_bytes(output).map_err(|_e| "Failed to parse spent output".to_string())
+        })
+        .collect()
+}
+
+/// Scripts used to find the pools and orders of a dex (see `DexConfig`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DexScripts {
+    pub pool_script_hashes: Vec<ScriptHash>,
+    pub order_script_hashes: Vec<ScriptHash>,
+    pub order_addresses: Vec<cml_chain::address::Address>,
+    pub pool_nft_policy_id: Option<PolicyId>,
+}
+
+impl DexScripts {
+    /// Replaces the default scripts of the dex with the ones set in the configuration
+    /// Fails if any of them can't be parsed
+    pub fn with_config(self, config: &DexConfig) -> Result<DexScripts, String> {
+        let parse_script_hashes = |hashes: &Vec<String>| {
+            hashes
+                .iter()
+                .map(|hash| parse_script_hash(hash))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(DexScripts {
+            pool_script_hashes: match &config.pool_script_hashes {
+                Some(hashes) => parse_script_hashes(hashes)?,
+                None => self.pool_script_hashes,
+            },
+            order_script_hashes: match &config.order_script_hashes {
+                Some(hashes) => parse_script_hashes(hashes)?,
+                None => self.order_script_hashes,
+            },
+            order_addresses: match &config.order_addresses {
+                Some(addresses) => addresses
+                    .iter()
+                    .map(|address| parse_address(address))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => self.order_addresses,
+            },
+            pool_nft_policy_id: match &config.pool_nft_policy_id {
+                Some(policy_id) => Some(parse_script_hash(policy_id)?),
+                None => self.pool_nft_policy_id,
+            },
+        })
+    }
+
+    /// Policy id of the LP tokens
+    pub fn lp_policy_id(&self) -> Result<&PolicyId, String> {
+        self.pool_nft_policy_id
+            .as_ref()
+            .ok_or_else(|| "No pool NFT policy configured".to_string())
+    }
+}
+
+/// Parses a hex-encoded script hash (or policy id) of the configuration
+pub fn parse_script_hash(hash: &str) -> Result<ScriptHash, String> {
+    ScriptHash::from_hex(hash).map_err(|err| format!("invalid script hash {hash}: {err}"))
+}
+
+/// Parses a bech32 address of the configuration
+pub fn parse_address(address: &str) -> Result<cml_chain::address::Address, String> {
+    cml_chain::address::Address::from_bech32(address)
+        .map_err(|err| format!("invalid address {address}: {err}"))
+}
+
 pub struct QueuedMeanPrice {
     pub tx_id: i64,
     pub address: Vec<u8>,
@@ -82,6 +165,20 @@ pub struct QueuedSwap {
     pub operation: Operation,
 }
 
+pub struct QueuedLiquidity {
+    pub tx_id: i64,
+    pub address: Vec<u8>,
+    pub owner: Vec<u8>,
+    pub dex_type: DexType,
+    pub asset1: AssetPair,
+    pub asset2: AssetPair,
+    pub amount1: u64,
+    pub amount2: u64,
+    pub lp_asset: (Vec<u8>, Vec<u8>),
+    pub lp_amount: u64,
+    pub operation: LiquidityOperation,
+}
+
 pub trait Dex {
     /// Handle the rest of the assets on the pool address
     fn queue_mean_price(
@@ -101,46 +198,53 @@ pub trait Dex {
         tx_id: i64,
         multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
     ) -> Result<(), String>;
-}
-
-#[derive(Debug, PartialEq, Eq)]
-pub struct WingRidersV1;
-#[derive(Debug, PartialEq, Eq)]
-pub struct MinSwapV1;
-#[derive(Debug, PartialEq, Eq)]
-pub struct SundaeSwapV1;
-#[derive(Debug, PartialEq, Eq)]
-pub struct Empty;
 
-impl Dex for Empty {
-    fn queue_mean_price(
+    /// Handle LP tokens and pool assets of each deposit and withdrawal
+    /// note: dexes that don't implement it never report any liquidity event
+    fn queue_liquidity(
         &self,
-        _queued_prices: &mut Vec<QueuedMeanPrice>,
-        _tx: &cml_multi_era::MultiEraTransactionBody,
-        _tx_witness: &cml_chain::transaction::TransactionWitnessSet,
-        _tx_id: i64,
-    ) -> Result<(), String> {
-        unimplemented!();
-    }
-
-    fn queue_swap(
-        &self,
-        _queued_swaps: &mut Vec<QueuedSwap>,
+        _queued_liquidity: &mut Vec<QueuedLiquidity>,
         _tx: &cml_multi_era::MultiEraTransactionBody,
         _tx_witness: &cml_chain::transaction::TransactionWitnessSet,
         _tx_id: i64,
         _multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
     ) -> Result<(), String> {
-        unimplemented!();
+        Ok(())
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct WingRidersV1 {
+    pub scripts: DexScripts,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub struct MinSwapV1 {
+    pub scripts: DexScripts,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub struct SundaeSwapV1 {
+    pub scripts: DexScripts,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub struct MinSwapV2 {
+    pub scripts: DexScripts,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub struct SundaeSwapV3 {
+    pub scripts: DexScripts,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub struct WingRidersV2 {
+    pub scripts: DexScripts,
+}
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DexType {
     WingRidersV1,
     SundaeSwapV1,
     MinSwapV1,
     MinSwapV2,
+    SundaeSwapV3,
+    WingRidersV2,
 }
 
 impl From<DexType> for i32 {
@@ -150,18 +254,49 @@ impl From<DexType> for i32 {
             DexType::SundaeSwapV1 => 1,
             DexType::MinSwapV1 => 2,
             DexType::MinSwapV2 => 3,
+            DexType::SundaeSwapV3 => 4,
+            DexType::WingRidersV2 => 5,
         }
     }
 }
 
 impl DexType {
-    fn as_trait(&self) -> &dyn Dex {
-        match &self {
-            DexType::WingRidersV1 => &WingRidersV1 {},
-            DexType::MinSwapV1 => &MinSwapV1 {},
-            DexType::SundaeSwapV1 => &SundaeSwapV1 {},
-            _ => &Empty {},
-        }
+    fn as_trait(&self, config: &DexConfig) -> Result<Box<dyn Dex>, DbErr> {
+        let invalid_config =
+            |err: String| DbErr::Custom(format!("invalid configuration for {self:?}: {err}"));
+        let dex: Box<dyn Dex> = match &self {
+            DexType::WingRidersV1 => Box::new(WingRidersV1 {
+                scripts: wingriders_v1::mainnet_scripts()
+                    .with_config(config)
+                    .map_err(invalid_config)?,
+            }),
+            DexType::MinSwapV1 => Box::new(MinSwapV1 {
+                scripts: minswap_v1::mainnet_scripts()
+                    .with_config(config)
+                    .map_err(invalid_config)?,
+            }),
+            DexType::SundaeSwapV1 => Box::new(SundaeSwapV1 {
+                scripts: sundaeswap_v1::mainnet_scripts()
+                    .with_config(config)
+                    .map_err(invalid_config)?,
+            }),
+            DexType::MinSwapV2 => Box::new(MinSwapV2 {
+                scripts: minswap_v2::mainnet_scripts()
+                    .with_config(config)
+                    .map_err(invalid_config)?,
+            }),
+            DexType::SundaeSwapV3 => Box::new(SundaeSwapV3 {
+                scripts: sundaeswap_v3::mainnet_scripts()
+                    .with_config(config)
+                    .map_err(invalid_config)?,
+            }),
+            DexType::WingRidersV2 => Box::new(WingRidersV2 {
+                scripts: wingriders_v2::mainnet_scripts()
+                    .with_config(config)
+                    .map_err(invalid_config)?,
+            }),
+        };
+        Ok(dex)
     }
 }
 
@@ -171,10 +306,11 @@ pub async fn handle_mean_price(
     multiera_txs: &[TransactionModel],
     multiera_addresses: &BTreeMap<Vec<u8>, AddressInBlock>,
     pool_type: DexType,
+    config: &DexConfig,
 ) -> Result<(), DbErr> {
     // 1) Parse mean prices
     let pool = pool_type;
-    let mean_value_trait = pool.as_trait();
+    let mean_value_trait = pool.as_trait(config)?;
     let mut queued_prices = Vec::<QueuedMeanPrice>::default();
     for ((tx_body, tx_witness_set), cardano_transaction) in block
         .1
@@ -281,9 +417,10 @@ pub async fn handle_swap(
     multiera_addresses: &BTreeMap<Vec<u8>, AddressInBlock>,
     multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
     dex_type: DexType,
+    config: &DexConfig,
 ) -> Result<(), DbErr> {
     // 1) Parse swaps
-    let swap_trait = dex_type.as_trait();
+    let swap_trait = dex_type.as_trait(config)?;
     let mut queued_swaps = Vec::<QueuedSwap>::default();
     for ((tx_body, tx_witness_set), cardano_transaction) in block
         .1
@@ -368,6 +505,135 @@ pub async fn handle_swap(
     Ok(())
 }
 
+pub async fn handle_liquidity(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+    multiera_addresses: &BTreeMap<Vec<u8>, AddressInBlock>,
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+    dex_type: DexType,
+    config: &DexConfig,
+) -> Result<(), DbErr> {
+    // 1) Parse deposits and withdrawals
+    let liquidity_trait = dex_type.as_trait(config)?;
+    let mut queued_liquidity = Vec::<QueuedLiquidity>::default();
+    for ((tx_body, tx_witness_set), cardano_transaction) in block
+        .1
+        .transaction_bodies()
+        .iter()
+        .zip(block.1.transaction_witness_sets())
+        .zip(multiera_txs)
+    {
+        if cardano_transaction.is_valid {
+            let result = liquidity_trait.queue_liquidity(
+                &mut queued_liquidity,
+                tx_body,
+                &tx_witness_set,
+                cardano_transaction.id,
+                multiera_used_inputs_to_outputs_map,
+            );
+            if result.is_err() {
+                tracing::warn!(
+                    "Failed to parse liquidity for tx {}: {}",
+                    cardano_transaction.id,
+                    result.err().unwrap()
+                );
+            }
+        }
+    }
+
+    if queued_liquidity.is_empty() {
+        return Ok(());
+    }
+
+    // 2) Remove asset duplicates to build a list of all the <policy_id, asset_name> to query for.
+    // ADA is ignored, it's not in the NativeAsset DB table
+    let mut unique_tokens = BTreeSet::<&(Vec<u8>, Vec<u8>)>::default();
+    for p in &queued_liquidity {
+        if let Some(pair) = &p.asset1 {
+            unique_tokens.insert(pair);
+        }
+        if let Some(pair) = &p.asset2 {
+            unique_tokens.insert(pair);
+        }
+        unique_tokens.insert(&p.lp_asset);
+    }
+
+    // 3) Query for asset ids
+    let found_assets = asset_from_pair(
+        db_tx,
+        &unique_tokens
+            .iter()
+            .map(|(policy_id, asset_name)| (policy_id.clone(), asset_name.clone()))
+            .collect::<Vec<_>>(),
+    )
+    .await?;
+    let mut asset_pair_to_id_map = found_assets
+        .into_iter()
+        .map(|asset| (Some((asset.policy_id, asset.asset_name)), Some(asset.id)))
+        .collect::<BTreeMap<_, _>>();
+    asset_pair_to_id_map.insert(None, None); // ADA
+
+    // 4) Add deposits and withdrawals to DB
+    let to_add = queued_liquidity
+        .iter()
+        .filter_map(|liquidity| {
+            // In the unlikely case that an asset or the owner is not in the DB, skip this event
+            let lp_asset_id = (*asset_pair_to_id_map.get(&Some(liquidity.lp_asset.clone()))?)?;
+            let owner = multiera_addresses.get(&liquidity.owner)?;
+            if !asset_pair_to_id_map.contains_key(&liquidity.asset1)
+                || !asset_pair_to_id_map.contains_key(&liquidity.asset2)
+            {
+                return None;
+            }
+            Some(DexLiquidityActiveModel {
+                tx_id: Set(liquidity.tx_id),
+                address_id: Set(multiera_addresses[&liquidity.address].model.id),
+                owner_address_id: Set(owner.model.id),
+                dex: Set(liquidity.dex_type.clone().into()),
+                asset1_id: Set(asset_pair_to_id_map[&liquidity.asset1]),
+                asset2_id: Set(asset_pair_to_id_map[&liquidity.asset2]),
+                amount1: Set(liquidity.amount1),
+                amount2: Set(liquidity.amount2),
+                lp_asset_id: Set(lp_asset_id),
+                lp_amount: Set(liquidity.lp_amount),
+                operation: Set(liquidity.operation.into()),
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+    if !to_add.is_empty() {
+        DexLiquidity::insert_many(to_add).exec(db_tx).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the LP token held by `output` and its amount
+/// LP tokens are found by policy id since their asset name depends on the pool
+pub fn get_lp_asset(
+    output: &cml_multi_era::utils::MultiEraTransactionOutput,
+    lp_policy_id: &PolicyId,
+) -> Option<((Vec<u8>, Vec<u8>), u64)> {
+    output
+        .amount()
+        .multiasset
+        .iter()
+        .filter(|(policy_id, _assets)| *policy_id == lp_policy_id)
+        .flat_map(|(policy_id, assets)| {
+            assets.iter().map(|(asset_name, value)| {
+                (
+                    (
+                        policy_id.to_raw_bytes().to_vec(),
+                        asset_name.to_raw_bytes().to_vec(),
+                    ),
+                    *value,
+                )
+            })
+        })
+        .next()
+}
+
 pub fn datum_to_json(datum: &cml_chain::plutus::PlutusData) -> Result<serde_json::Value, String> {
     let value =
         decode_plutus_datum_to_json_str(datum, CardanoNodePlutusDatumSchema::DetailedSchema)
@@ -375,9 +641,54 @@ pub fn datum_to_json(datum: &cml_chain::plutus::PlutusData) -> Result<serde_json
     serde_json::from_str(&value).map_err(|err| format!("can't decode json: {err}"))
 }
 
+/// Builds the address described by a plutus `Address` (in the json format of `datum_to_json`)
+/// note: pointer addresses aren't supported
+pub fn address_from_datum(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<cml_chain::address::Address, String> {
+    // Credential = PubKeyCredential (constructor 0) | ScriptCredential (constructor 1)
+    let parse_credential = |credential: &serde_json::Value| -> Result<(u8, String), String> {
+        let credential_type = credential["constructor"]
+            .as_u64()
+            .ok_or("Failed to parse credential type")?;
+        let hash = credential["fields"][0]["bytes"]
+            .as_str()
+            .ok_or("Failed to parse credential hash")?;
+        Ok((credential_type as u8, hash.to_string()))
+    };
+
+    let (payment_type, payment_hash) = parse_credential(&datum["fields"][0])?;
+    let stake_credential = &datum["fields"][1];
+    let (header, stake_hash) = match (
+        stake_credential["constructor"].as_u64(),
+        stake_credential["fields"][0]["constructor"].as_u64(),
+    ) {
+        // Some(StakingHash(credential)): base address
+        (Some(0), Some(0)) => {
+            let (stake_type, stake_hash) =
+                parse_credential(&stake_credential["fields"][0]["fields"][0])?;
+            (payment_type | (stake_type << 1), stake_hash)
+        }
+        // None: enterprise address
+        (Some(1), _) => (0b0110 | payment_type, String::new()),
+        _ => return Err("Unsupported stake credential".to_string()),
+    };
+
+    cml_chain::address::Address::from_hex(&format!(
+        "{:02x}{}{}",
+        (header << 4) | network_id,
+        payment_hash,
+        stake_hash
+    ))
+    .map_err(|_e| "Failed to parse address".to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::multiera::dex::common::datum_to_json;
+    use crate::config::DexConfig::DexConfig;
+    use crate::multiera::dex::common::{address_from_datum, datum_to_json, parse_script_hash};
+    use crate::multiera::dex::minswap_v1;
     use cml_chain::plutus::PlutusData;
     use cml_core::serialization::FromBytes;
 
@@ -399,4 +710,119 @@ mod tests {
             "c72d0438330ed1346f4437fcc1c263ea38e933c1124c8d0f2abc6312"
         );
     }
+
+    #[test]
+    fn base_address_from_datum() {
+        let bytes = hex::decode("d8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffff").unwrap();
+        let datum_json = datum_to_json(&PlutusData::from_bytes(bytes).unwrap()).unwrap();
+
+        let address = address_from_datum(&datum_json, 1).unwrap();
+        assert_eq!(
+            hex::encode(address.to_raw_bytes()),
+            "01f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121"
+        );
+    }
+
+    #[test]
+    fn enterprise_script_address_from_datum() {
+        let bytes = hex::decode(
+            "d8799fd87a9f581c913ffdc7c2f0fe3a4ab1c5fb8d7d16f98a9a66a9a2c8f7e3bcb2b9caffd87a80ff",
+        )
+        .unwrap();
+        let datum_json = datum_to_json(&PlutusData::from_bytes(bytes).unwrap()).unwrap();
+
+        let address = address_from_datum(&datum_json, 0).unwrap();
+        assert_eq!(
+            hex::encode(address.to_raw_bytes()),
+            "70913ffdc7c2f0fe3a4ab1c5fb8d7d16f98a9a66a9a2c8f7e3bcb2b9ca"
+        );
+    }
+
+    #[test]
+    fn scripts_with_config() {
+        let config = DexConfig {
+            pool_script_hashes: Some(vec![
+                "913ffdc7c2f0fe3a4ab1c5fb8d7d16f98a9a66a9a2c8f7e3bcb2b9ca".to_string(),
+            ]),
+            ..Default::default()
+        };
+        let scripts = minswap_v1::mainnet_scripts().with_config(&config).unwrap();
+        assert_eq!(
+            scripts.pool_script_hashes,
+            vec![
+                parse_script_hash("913ffdc7c2f0fe3a4ab1c5fb8d7d16f98a9a66a9a2c8f7e3bcb2b9ca")
+                    .unwrap()
+            ]
+        );
+        // fields that aren't overridden keep the mainnet value
+        assert_eq!(
+            scripts.order_addresses,
+            minswap_v1::mainnet_scripts().order_addresses
+        );
+
+        let invalid_hash = DexConfig {
+            pool_script_hashes: Some(vec!["913ffdc7".to_string()]),
+            ..Default::default()
+        };
+        assert!(minswap_v1::mainnet_scripts()
+            .with_config(&invalid_hash)
+            .is_err());
+
+        let invalid_address = DexConfig {
+            order_addresses: Some(vec!["addr1invalid".to_string()]),
+            ..Default::default()
+        };
+        assert!(minswap_v1::mainnet_scripts()
+            .with_config(&invalid_address)
+            .is_err());
+    }
+}
+
+/// Helpers to run the dexes on transactions given as cbor
+#[cfg(test)]
+pub mod test_utils {
+    use crate::era_common::OutputWithTxData;
+    use cml_core::serialization::Deserialize;
+    use cml_crypto::RawBytesEncoding;
+    use entity::{block::EraValue, prelude::TransactionOutputModel};
+    use std::collections::BTreeMap;
+
+    pub fn tx_body(cbor_hex: &str) -> cml_multi_era::MultiEraTransactionBody {
+        cml_multi_era::MultiEraTransactionBody::Conway(
+            cml_chain::transaction::TransactionBody::from_cbor_bytes(
+                &hex::decode(cbor_hex).unwrap(),
+            )
+            .unwrap(),
+        )
+    }
+
+    /// Outputs spent by `tx`, as built by `MultieraUsedInputTask`
+    /// from the cbor of the output spent by each input (in the order of the inputs)
+    pub fn spent_outputs_map(
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        spent_outputs_cbor_hex: &[&str],
+    ) -> BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>> {
+        let mut map = BTreeMap::<Vec<u8>, BTreeMap<i64, OutputWithTxData>>::new();
+        for (id, (input, output_cbor_hex)) in
+            tx.inputs().iter().zip(spent_outputs_cbor_hex).enumerate()
+        {
+            let tx_hash = input.hash().unwrap().to_raw_bytes().to_vec();
+            let output_index = input.index().unwrap();
+            map.entry(tx_hash.clone()).or_default().insert(
+                output_index as i64,
+                OutputWithTxData {
+                    model: TransactionOutputModel {
+                        id: id as i64,
+                        payload: hex::decode(output_cbor_hex).unwrap(),
+                        address_id: 0,
+                        tx_id: 0,
+                        output_index: output_index as i32,
+                    },
+                    tx_hash,
+                    era: EraValue::Babbage,
+                },
+            );
+        }
+        map
+    }
 }
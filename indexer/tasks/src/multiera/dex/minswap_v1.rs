@@ -7,10 +7,10 @@ use std::collections::BTreeMap;
 
 use super::common::{
     build_asset, filter_outputs_and_datums_by_address, filter_outputs_and_datums_by_hash,
-    reduce_ada_amount, Dex, DexType, MinSwapV1, QueuedMeanPrice, QueuedSwap,
+    get_spent_outputs, parse_address, parse_script_hash, reduce_ada_amount, Dex, DexScripts,
+    DexType, MinSwapV1, QueuedMeanPrice, QueuedSwap,
 };
 use crate::multiera::dex::common::datum_to_json;
-use crate::multiera::utils::common::output_from_bytes;
 use crate::{era_common::OutputWithTxData, multiera::utils::common::get_asset_amount};
 use entity::dex_swap::Operation;
 
@@ -21,6 +21,21 @@ pub const BATCH_ORDER_ADDRESS2: &str = "addr1wxn9efv2f6w82hagxqtn62ju4m293tqvw0u
 pub const SWAP_IN_ADA: u64 = 4_000_000; // oil ADA + agent fee
 pub const SWAP_OUT_ADA: u64 = 2_000_000; // oil ADA
 
+/// Mainnet scripts, used unless the execution plan overrides them
+pub fn mainnet_scripts() -> DexScripts {
+    DexScripts {
+        pool_script_hashes: vec![
+            parse_script_hash(POOL_SCRIPT_HASH1).unwrap(),
+            parse_script_hash(POOL_SCRIPT_HASH2).unwrap(),
+        ],
+        order_addresses: vec![
+            parse_address(BATCH_ORDER_ADDRESS1).unwrap(),
+            parse_address(BATCH_ORDER_ADDRESS2).unwrap(),
+        ],
+        ..Default::default()
+    }
+}
+
 impl Dex for MinSwapV1 {
     fn queue_mean_price(
         &self,
@@ -32,7 +47,7 @@ impl Dex for MinSwapV1 {
         // Note: there should be at most one pool output
         if let Some((output, datum)) = filter_outputs_and_datums_by_hash(
             &tx.outputs(),
-            &[POOL_SCRIPT_HASH1, POOL_SCRIPT_HASH2],
+            &self.scripts.pool_script_hashes,
             &tx_witness.plutus_datums,
         )
         .first()
@@ -76,7 +91,7 @@ impl Dex for MinSwapV1 {
         // Note: there should be at most one pool output
         if let Some((main_output, main_datum)) = filter_outputs_and_datums_by_hash(
             &tx.outputs(),
-            &[POOL_SCRIPT_HASH1, POOL_SCRIPT_HASH2],
+            &self.scripts.pool_script_hashes,
             &tx_witness.plutus_datums,
         )
         .first()
@@ -96,19 +111,10 @@ impl Dex for MinSwapV1 {
             let asset1 = build_asset(parse_asset_item(0, 0)?, parse_asset_item(0, 1)?);
             let asset2 = build_asset(parse_asset_item(1, 0)?, parse_asset_item(1, 1)?);
 
-            let inputs: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx
-                .inputs()
-                .iter()
-                .map(|i| {
-                    let output = &multiera_used_inputs_to_outputs_map
-                        [&i.hash().unwrap().to_raw_bytes().to_vec()]
-                        [&(i.index().unwrap() as i64)];
-                    output_from_bytes(output).unwrap()
-                })
-                .collect::<Vec<_>>();
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
             for (input, input_datum) in filter_outputs_and_datums_by_address(
                 &inputs,
-                &[BATCH_ORDER_ADDRESS1, BATCH_ORDER_ADDRESS2],
+                &self.scripts.order_addresses,
                 &tx_witness.plutus_datums,
             ) {
                 let input_datum = datum_to_json(&input_datum)?;
@@ -0,0 +1,431 @@
+use cml_crypto::RawBytesEncoding;
+use std::collections::BTreeMap;
+
+use super::common::{
+    address_from_datum, build_asset, filter_outputs_and_datums_by_hash, get_lp_asset,
+    get_spent_outputs, parse_script_hash, reduce_ada_amount, Dex, DexScripts, DexType, MinSwapV2,
+    QueuedLiquidity, QueuedMeanPrice, QueuedSwap,
+};
+use crate::multiera::dex::common::datum_to_json;
+use crate::types::AssetPair;
+use crate::{era_common::OutputWithTxData, multiera::utils::common::get_asset_amount};
+use entity::dex_liquidity::Operation as LiquidityOperation;
+use entity::dex_swap::Operation;
+
+pub const POOL_SCRIPT_HASH: &str = "ea07b733d932129c378af627436e7cbc2ef0bf96e0036bb51b3bde6b";
+pub const ORDER_SCRIPT_HASH: &str = "c3e28c36c3447315ba5a56f33da6a6ddc1770a876a8d9f0cb3a97c4c";
+pub const LP_POLICY_ID: &str = "f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c";
+pub const ORDER_DEPOSIT_ADA: u64 = 2_000_000; // returned to the receiver with the swap result
+
+/// Content of a pool datum needed to compute prices
+#[derive(Debug, PartialEq, Eq)]
+struct PoolDatum {
+    asset_a: AssetPair,
+    asset_b: AssetPair,
+    // note: the pool UTxO also holds ADA that isn't part of the reserves
+    reserve_a: u64,
+    reserve_b: u64,
+}
+
+/// Content of a swap order datum needed to find the swap result
+#[derive(Debug, PartialEq)]
+struct SwapOrderDatum {
+    a_to_b: bool,
+    receiver: cml_chain::address::Address,
+    max_batcher_fee: u64,
+}
+
+/// Content of a deposit or withdrawal order datum needed to find the LP tokens
+#[derive(Debug, PartialEq)]
+struct LiquidityOrderDatum {
+    operation: LiquidityOperation,
+    receiver: cml_chain::address::Address,
+    max_batcher_fee: u64,
+}
+
+fn parse_pool_datum(datum: &serde_json::Value) -> Result<PoolDatum, String> {
+    let parse_asset_item = |i, j| -> Result<Vec<u8>, &str> {
+        let item = datum["fields"][i]["fields"][j]["bytes"]
+            .as_str()
+            .ok_or("Failed to parse asset item")?
+            .to_string();
+        hex::decode(item).map_err(|_e| "Failed to parse asset item")
+    };
+    let parse_int = |i| -> Result<u64, &str> {
+        datum["fields"][i]["int"]
+            .as_u64()
+            .ok_or("Failed to parse reserve")
+    };
+
+    Ok(PoolDatum {
+        asset_a: build_asset(parse_asset_item(1, 0)?, parse_asset_item(1, 1)?),
+        asset_b: build_asset(parse_asset_item(2, 0)?, parse_asset_item(2, 1)?),
+        reserve_a: parse_int(4)?,
+        reserve_b: parse_int(5)?,
+    })
+}
+
+/// Returns None if the order isn't a swap (ex: deposit, withdrawal)
+fn parse_swap_order_datum(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<Option<SwapOrderDatum>, String> {
+    // identify operation: 0 = swap exact in, 1 = stop loss, 2 = OCO, 3 = swap exact out
+    // all of them start with the direction of the swap
+    let step = &datum["fields"][6];
+    let operation = step["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse operation")?;
+    if operation > 3 {
+        return Ok(None);
+    }
+    // direction is a boolean: False = constructor 0, True = constructor 1
+    let a_to_b = step["fields"][0]["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse swap direction")?
+        == 1;
+
+    Ok(Some(SwapOrderDatum {
+        a_to_b,
+        receiver: address_from_datum(&datum["fields"][3], network_id)?,
+        max_batcher_fee: datum["fields"][7]["int"]
+            .as_u64()
+            .ok_or("Failed to parse batcher fee")?,
+    }))
+}
+
+/// Returns None if the order isn't a deposit or a withdrawal
+/// note: zap out and imbalanced withdrawals aren't supported
+fn parse_liquidity_order_datum(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<Option<LiquidityOrderDatum>, String> {
+    // identify operation: 4 = deposit, 5 = withdraw
+    let operation = match datum["fields"][6]["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse operation")?
+    {
+        4 => LiquidityOperation::Deposit,
+        5 => LiquidityOperation::Withdrawal,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(LiquidityOrderDatum {
+        operation,
+        receiver: address_from_datum(&datum["fields"][3], network_id)?,
+        max_batcher_fee: datum["fields"][7]["int"]
+            .as_u64()
+            .ok_or("Failed to parse batcher fee")?,
+    }))
+}
+
+/// Mainnet scripts, used unless the execution plan overrides them
+pub fn mainnet_scripts() -> DexScripts {
+    DexScripts {
+        pool_script_hashes: vec![parse_script_hash(POOL_SCRIPT_HASH).unwrap()],
+        order_script_hashes: vec![parse_script_hash(ORDER_SCRIPT_HASH).unwrap()],
+        pool_nft_policy_id: Some(parse_script_hash(LP_POLICY_ID).unwrap()),
+        ..Default::default()
+    }
+}
+
+impl Dex for MinSwapV2 {
+    fn queue_mean_price(
+        &self,
+        queued_prices: &mut Vec<QueuedMeanPrice>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+    ) -> Result<(), String> {
+        // Note: there can be multiple pool outputs when orders are routed through several pools
+        for (output, datum) in filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        ) {
+            let datum = parse_pool_datum(&datum_to_json(&datum)?)?;
+
+            queued_prices.push(QueuedMeanPrice {
+                tx_id,
+                address: output.address().to_raw_bytes().to_vec(),
+                dex_type: DexType::MinSwapV2,
+                asset1: datum.asset_a,
+                asset2: datum.asset_b,
+                amount1: datum.reserve_a,
+                amount2: datum.reserve_b,
+            });
+        }
+        Ok(())
+    }
+
+    fn queue_swap(
+        &self,
+        queued_swaps: &mut Vec<QueuedSwap>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+        multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        // except for orders routed through several pools, which aren't supported
+        if let Some((main_output, main_datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let pool = parse_pool_datum(&datum_to_json(main_datum)?)?;
+            let network_id = main_output
+                .address()
+                .network_id()
+                .map_err(|_e| "Failed to parse network id")?;
+
+            let mut free_utxos: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx.outputs();
+
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
+            for (input, input_datum) in filter_outputs_and_datums_by_hash(
+                &inputs,
+                &self.scripts.order_script_hashes,
+                &tx_witness.plutus_datums,
+            ) {
+                let input_datum = datum_to_json(&input_datum)?;
+                let order = match parse_swap_order_datum(&input_datum, network_id)? {
+                    Some(order) => order,
+                    None => {
+                        tracing::debug!("Operation is not a swap");
+                        continue;
+                    }
+                };
+
+                // Get coresponding UTxO with result
+                let utxo_pos = free_utxos
+                    .iter()
+                    .position(|o| o.address() == order.receiver)
+                    .ok_or("Failed to find utxo")?;
+                let utxo = free_utxos[utxo_pos].clone();
+                free_utxos.remove(utxo_pos);
+
+                // Get amount and direction
+                let swap_in_ada = ORDER_DEPOSIT_ADA + order.max_batcher_fee;
+                let amount1;
+                let amount2;
+                let operation;
+                if order.a_to_b {
+                    amount1 = get_asset_amount(&input, &pool.asset_a)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_a, swap_in_ada));
+                    amount2 = get_asset_amount(&utxo, &pool.asset_b)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_b, ORDER_DEPOSIT_ADA));
+                    operation = Operation::Sell;
+                } else {
+                    amount1 = get_asset_amount(&utxo, &pool.asset_a)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_a, ORDER_DEPOSIT_ADA));
+                    amount2 = get_asset_amount(&input, &pool.asset_b)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_b, swap_in_ada));
+                    operation = Operation::Buy;
+                }
+                queued_swaps.push(QueuedSwap {
+                    tx_id,
+                    address: main_output.address().to_raw_bytes().to_vec(),
+                    dex_type: DexType::MinSwapV2,
+                    asset1: pool.asset_a.clone(),
+                    asset2: pool.asset_b.clone(),
+                    amount1,
+                    amount2,
+                    operation,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn queue_liquidity(
+        &self,
+        queued_liquidity: &mut Vec<QueuedLiquidity>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+        multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        if let Some((main_output, main_datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let pool = parse_pool_datum(&datum_to_json(main_datum)?)?;
+            let network_id = main_output
+                .address()
+                .network_id()
+                .map_err(|_e| "Failed to parse network id")?;
+
+            let lp_policy_id = self.scripts.lp_policy_id()?;
+            let mut free_utxos: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx.outputs();
+
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
+            for (input, input_datum) in filter_outputs_and_datums_by_hash(
+                &inputs,
+                &self.scripts.order_script_hashes,
+                &tx_witness.plutus_datums,
+            ) {
+                let input_datum = datum_to_json(&input_datum)?;
+                let order = match parse_liquidity_order_datum(&input_datum, network_id)? {
+                    Some(order) => order,
+                    None => {
+                        tracing::debug!("Operation is not a deposit or a withdrawal");
+                        continue;
+                    }
+                };
+
+                // Get coresponding UTxO with result
+                let utxo_pos = free_utxos
+                    .iter()
+                    .position(|o| o.address() == order.receiver)
+                    .ok_or("Failed to find utxo")?;
+                let utxo = free_utxos[utxo_pos].clone();
+                free_utxos.remove(utxo_pos);
+
+                // Get amounts: pool assets go from the order to the pool for a deposit
+                // and from the pool to the receiver for a withdrawal (and the other way for LP tokens)
+                let order_in_ada = ORDER_DEPOSIT_ADA + order.max_batcher_fee;
+                let (amount1, amount2, (lp_asset, lp_amount)) = match order.operation {
+                    LiquidityOperation::Deposit => (
+                        get_asset_amount(&input, &pool.asset_a)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_a, order_in_ada)),
+                        get_asset_amount(&input, &pool.asset_b)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_b, order_in_ada)),
+                        get_lp_asset(&utxo, lp_policy_id).ok_or("Failed to find LP tokens")?,
+                    ),
+                    LiquidityOperation::Withdrawal => (
+                        get_asset_amount(&utxo, &pool.asset_a)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_a, ORDER_DEPOSIT_ADA)),
+                        get_asset_amount(&utxo, &pool.asset_b)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_b, ORDER_DEPOSIT_ADA)),
+                        get_lp_asset(&input, lp_policy_id).ok_or("Failed to find LP tokens")?,
+                    ),
+                };
+                queued_liquidity.push(QueuedLiquidity {
+                    tx_id,
+                    address: main_output.address().to_raw_bytes().to_vec(),
+                    owner: order.receiver.to_raw_bytes(),
+                    dex_type: DexType::MinSwapV2,
+                    asset1: pool.asset_a.clone(),
+                    asset2: pool.asset_b.clone(),
+                    amount1,
+                    amount2,
+                    lp_asset,
+                    lp_amount,
+                    operation: order.operation,
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mainnet_scripts;
+    use crate::multiera::dex::common::test_utils::{spent_outputs_map, tx_body};
+    use crate::multiera::dex::common::{Dex, MinSwapV2};
+    use cml_chain::transaction::TransactionWitnessSet;
+    use entity::dex_liquidity::Operation as LiquidityOperation;
+    use entity::dex_swap::Operation;
+
+    const POOL_INPUT: &str = "a300581d71ea07b733d932129c378af627436e7cbc2ef0bf96e0036bb51b3bde6b01821b000000012a05f200a1581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6a1434d494e1b0000001bf08eb000028201d818587bd8799fd8799fd87a9f581c913ffdc7c2f0fe3a4ab1c5fb8d7d16f98a9a66a9a2c8f7e3bcb2b9caffffd8799f4040ffd8799f581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6434d494eff1b0020c49ba5e353f71b000000012a05f2001b0000001bf08eb000181e181ed87a80d87980ff";
+
+    fn min() -> Option<(Vec<u8>, Vec<u8>)> {
+        Some((
+            hex::decode("29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6").unwrap(),
+            hex::decode("4d494e").unwrap(),
+        ))
+    }
+
+    #[test]
+    fn swap_exact_in_transaction() {
+        // 10 ADA sold for 239 MIN, the order also holds the deposit and the batcher fee
+        let tx = tx_body("a30082825820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00825820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb010182a300581d71ea07b733d932129c378af627436e7cbc2ef0bf96e0036bb51b3bde6b01821b000000012a9e8880a1581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6a1434d494e1b0000001be24fd640028201d818587bd8799fd8799fd87a9f581c913ffdc7c2f0fe3a4ab1c5fb8d7d16f98a9a66a9a2c8f7e3bcb2b9caffffd8799f4040ffd8799f581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6434d494eff1b0020c49ba5e353f71b000000012a05f2001b0000001bf08eb000181e181ed87a80d87980ffa200583901f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc412101821a001e8480a1581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6a1434d494e1a0e3ed9c0021a00030d40");
+        let spent_outputs = spent_outputs_map(
+            &tx,
+            &[
+                POOL_INPUT,
+                "a300581d71c3e28c36c3447315ba5a56f33da6a6ddc1770a876a8d9f0cb3a97c4c011a00c1c960028201d818590130d8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87980d8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87980d8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c582082e2b1fd27a7712a1a9cf750dfbea1a5778611b20e06dd6a611df7a643f8cb75ffd8799fd87a80d8799f1a00989680ff1a0db58580d87980ff1a000aae60d87a80ff",
+            ],
+        );
+        let dex = MinSwapV2 {
+            scripts: mainnet_scripts(),
+        };
+
+        let mut queued_prices = vec![];
+        dex.queue_mean_price(&mut queued_prices, &tx, &TransactionWitnessSet::new(), 0)
+            .unwrap();
+        assert_eq!(queued_prices.len(), 1);
+        assert_eq!(queued_prices[0].asset1, None);
+        assert_eq!(queued_prices[0].asset2, min());
+        assert_eq!(queued_prices[0].amount1, 5_000_000_000);
+        assert_eq!(queued_prices[0].amount2, 120_000_000_000);
+
+        let mut queued_swaps = vec![];
+        dex.queue_swap(
+            &mut queued_swaps,
+            &tx,
+            &TransactionWitnessSet::new(),
+            0,
+            &spent_outputs,
+        )
+        .unwrap();
+        assert_eq!(queued_swaps.len(), 1);
+        let swap = &queued_swaps[0];
+        assert_eq!(
+            hex::encode(&swap.address),
+            "71ea07b733d932129c378af627436e7cbc2ef0bf96e0036bb51b3bde6b"
+        );
+        assert_eq!(swap.asset1, None);
+        assert_eq!(swap.asset2, min());
+        assert_eq!(swap.amount1, 10_000_000);
+        assert_eq!(swap.amount2, 239_000_000);
+        assert!(swap.operation == Operation::Sell);
+    }
+
+    #[test]
+    fn deposit_transaction() {
+        // 10 ADA and 240 MIN deposited, the order also holds the deposit and the batcher fee
+        let tx = tx_body("a30082825820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00825820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb010182a300581d71ea07b733d932129c378af627436e7cbc2ef0bf96e0036bb51b3bde6b01821b000000012a9e8880a1581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6a1434d494e1b0000001bfedccc00028201d818587bd8799fd8799fd87a9f581c913ffdc7c2f0fe3a4ab1c5fb8d7d16f98a9a66a9a2c8f7e3bcb2b9caffffd8799f4040ffd8799f581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6434d494eff1b0020c49ba5e353f71b000000012a05f2001b0000001bf08eb000181e181ed87a80d87980ffa200583901f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc412101821a001e8480a1581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4ca1582082e2b1fd27a7712a1a9cf750dfbea1a5778611b20e06dd6a611df7a643f8cb751a02eb8662021a00030d40");
+        let spent_outputs = spent_outputs_map(&tx, &[POOL_INPUT, "a300581d71c3e28c36c3447315ba5a56f33da6a6ddc1770a876a8d9f0cb3a97c4c01821a00c1c960a1581c29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6a1434d494e1a0e4e1c00028201d81859012cd8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87980d8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87980d8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c582082e2b1fd27a7712a1a9cf750dfbea1a5778611b20e06dd6a611df7a643f8cb75ffd87d9fd8799f1a00989680191388ff01d87980ff1a000aae60d87a80ff"]);
+        let dex = MinSwapV2 {
+            scripts: mainnet_scripts(),
+        };
+
+        let mut queued_liquidity = vec![];
+        dex.queue_liquidity(
+            &mut queued_liquidity,
+            &tx,
+            &TransactionWitnessSet::new(),
+            0,
+            &spent_outputs,
+        )
+        .unwrap();
+        assert_eq!(queued_liquidity.len(), 1);
+        let liquidity = &queued_liquidity[0];
+        assert_eq!(
+            hex::encode(&liquidity.owner),
+            "01f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121"
+        );
+        assert_eq!(liquidity.asset1, None);
+        assert_eq!(liquidity.asset2, min());
+        assert_eq!(liquidity.amount1, 10_000_000);
+        assert_eq!(liquidity.amount2, 240_000_000);
+        assert_eq!(
+            liquidity.lp_asset,
+            (
+                hex::decode("f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c").unwrap(),
+                hex::decode("82e2b1fd27a7712a1a9cf750dfbea1a5778611b20e06dd6a611df7a643f8cb75")
+                    .unwrap()
+            )
+        );
+        assert_eq!(liquidity.lp_amount, 48_989_794);
+        assert_eq!(liquidity.operation, LiquidityOperation::Deposit);
+    }
+}
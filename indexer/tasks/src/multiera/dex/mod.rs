@@ -1,4 +1,7 @@
 pub mod common;
 pub mod minswap_v1;
+pub mod minswap_v2;
 pub mod sundaeswap_v1;
+pub mod sundaeswap_v3;
 pub mod wingriders_v1;
+pub mod wingriders_v2;
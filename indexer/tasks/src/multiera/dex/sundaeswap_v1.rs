@@ -9,10 +9,10 @@ use sea_orm::DbErr;
 
 use super::common::{
     build_asset, filter_outputs_and_datums_by_address, filter_outputs_and_datums_by_hash,
-    reduce_ada_amount, Dex, DexType, QueuedMeanPrice, QueuedSwap, SundaeSwapV1,
+    get_spent_outputs, parse_script_hash, reduce_ada_amount, Dex, DexScripts, DexType,
+    QueuedMeanPrice, QueuedSwap, SundaeSwapV1,
 };
 use crate::multiera::dex::common::datum_to_json;
-use crate::multiera::utils::common::output_from_bytes;
 use crate::{era_common::OutputWithTxData, multiera::utils::common::get_asset_amount};
 use entity::dex_swap::Operation;
 
@@ -21,6 +21,15 @@ pub const REQUEST_SCRIPT_HASH: &str = "ba158766c1bae60e2117ee8987621441fac66a5e0
 pub const SWAP_IN_ADA: u64 = 4_500_000; // oil ADA + agent fee
 pub const SWAP_OUT_ADA: u64 = 2_000_000; // oil ADA
 
+/// Mainnet scripts, used unless the execution plan overrides them
+pub fn mainnet_scripts() -> DexScripts {
+    DexScripts {
+        pool_script_hashes: vec![parse_script_hash(POOL_SCRIPT_HASH).unwrap()],
+        order_script_hashes: vec![parse_script_hash(REQUEST_SCRIPT_HASH).unwrap()],
+        ..Default::default()
+    }
+}
+
 impl Dex for SundaeSwapV1 {
     fn queue_mean_price(
         &self,
@@ -32,7 +41,7 @@ impl Dex for SundaeSwapV1 {
         // Note: there should be at most one pool output
         if let Some((output, datum)) = filter_outputs_and_datums_by_hash(
             &tx.outputs(),
-            &[POOL_SCRIPT_HASH],
+            &self.scripts.pool_script_hashes,
             &tx_witness.plutus_datums,
         )
         .first()
@@ -76,7 +85,7 @@ impl Dex for SundaeSwapV1 {
         // Note: there should be at most one pool output
         if let Some((main_output, main_datum)) = filter_outputs_and_datums_by_hash(
             &tx.outputs(),
-            &[POOL_SCRIPT_HASH],
+            &self.scripts.pool_script_hashes,
             &tx_witness.plutus_datums,
         )
         .first()
@@ -96,19 +105,10 @@ impl Dex for SundaeSwapV1 {
             let asset1 = build_asset(parse_asset_item(0, 0)?, parse_asset_item(0, 1)?);
             let asset2 = build_asset(parse_asset_item(1, 0)?, parse_asset_item(1, 1)?);
 
-            let inputs: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx
-                .inputs()
-                .iter()
-                .map(|i| {
-                    let output = &multiera_used_inputs_to_outputs_map
-                        [&i.hash().unwrap().to_raw_bytes().to_vec()]
-                        [&(i.index().unwrap() as i64)];
-                    output_from_bytes(output).unwrap()
-                })
-                .collect::<Vec<_>>();
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
             for (input, input_datum) in filter_outputs_and_datums_by_hash(
                 &inputs,
-                &[REQUEST_SCRIPT_HASH],
+                &self.scripts.order_script_hashes,
                 &tx_witness.plutus_datums.clone(),
             ) {
                 let input_datum = datum_to_json(&input_datum)?;
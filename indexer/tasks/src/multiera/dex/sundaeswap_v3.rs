@@ -0,0 +1,510 @@
+use cml_crypto::RawBytesEncoding;
+use std::collections::BTreeMap;
+
+use super::common::{
+    address_from_datum, build_asset, filter_outputs_and_datums_by_hash, get_lp_asset,
+    get_spent_outputs, parse_script_hash, reduce_ada_amount, Dex, DexScripts, DexType,
+    QueuedLiquidity, QueuedMeanPrice, QueuedSwap, SundaeSwapV3,
+};
+use crate::multiera::dex::common::datum_to_json;
+use crate::types::AssetPair;
+use crate::{era_common::OutputWithTxData, multiera::utils::common::get_asset_amount};
+use entity::dex_liquidity::Operation as LiquidityOperation;
+use entity::dex_swap::Operation;
+
+pub const POOL_SCRIPT_HASH: &str = "e0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312b";
+pub const ORDER_SCRIPT_HASH: &str = "fa6a58bbe2d0ff05534431c8e2f0ef2cbdc1602a8456e4b13c8f3077";
+pub const ORDER_DEPOSIT_ADA: u64 = 2_000_000; // returned to the destination with the swap result
+
+/// Content of a pool datum needed to compute prices
+#[derive(Debug, PartialEq, Eq)]
+struct PoolDatum {
+    asset_a: AssetPair,
+    asset_b: AssetPair,
+    // note: protocol fees are kept in the pool UTxO until they are withdrawn
+    protocol_fees: u64,
+}
+
+/// Content of a swap order datum needed to find the swap result
+#[derive(Debug, PartialEq)]
+struct SwapOrderDatum {
+    offer_asset: AssetPair,
+    offer_amount: u64,
+    destination: cml_chain::address::Address,
+}
+
+/// Content of a deposit or withdrawal order datum needed to find the LP tokens
+#[derive(Debug, PartialEq)]
+struct LiquidityOrderDatum {
+    order: LiquidityOrder,
+    destination: cml_chain::address::Address,
+}
+
+#[derive(Debug, PartialEq)]
+enum LiquidityOrder {
+    /// exact amounts of the pool assets (in the pool order)
+    Deposit(u64, u64),
+    /// exact amount of LP tokens
+    Withdrawal((Vec<u8>, Vec<u8>), u64),
+}
+
+fn parse_pool_datum(datum: &serde_json::Value) -> Result<PoolDatum, String> {
+    // assets are a tuple of (policy id, asset name) tuples
+    let parse_asset_item = |i, j| -> Result<Vec<u8>, &str> {
+        let item = datum["fields"][1]["list"][i]["list"][j]["bytes"]
+            .as_str()
+            .ok_or("Failed to parse asset item")?
+            .to_string();
+        hex::decode(item).map_err(|_e| "Failed to parse asset item")
+    };
+
+    Ok(PoolDatum {
+        asset_a: build_asset(parse_asset_item(0, 0)?, parse_asset_item(0, 1)?),
+        asset_b: build_asset(parse_asset_item(1, 0)?, parse_asset_item(1, 1)?),
+        protocol_fees: datum["fields"][7]["int"]
+            .as_u64()
+            .ok_or("Failed to parse protocol fees")?,
+    })
+}
+
+/// Returns None if the order isn't a swap (ex: deposit, withdrawal)
+/// or if its result isn't sent to a fixed destination
+fn parse_swap_order_datum(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<Option<SwapOrderDatum>, String> {
+    // identify operation: 1 = swap
+    let details = &datum["fields"][4];
+    let operation = details["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse operation")?;
+    if operation != 1 {
+        return Ok(None);
+    }
+
+    // offer is a (policy id, asset name, amount) tuple
+    let offer = &details["fields"][0]["list"];
+    let parse_offer_item = |i| -> Result<Vec<u8>, &str> {
+        let item = offer[i]["bytes"]
+            .as_str()
+            .ok_or("Failed to parse offer item")?
+            .to_string();
+        hex::decode(item).map_err(|_e| "Failed to parse offer item")
+    };
+    let destination = match parse_destination(datum, network_id)? {
+        Some(destination) => destination,
+        None => return Ok(None),
+    };
+
+    Ok(Some(SwapOrderDatum {
+        offer_asset: build_asset(parse_offer_item(0)?, parse_offer_item(1)?),
+        offer_amount: offer[2]["int"]
+            .as_u64()
+            .ok_or("Failed to parse offer amount")?,
+        destination,
+    }))
+}
+
+/// Returns None if the order isn't a deposit or a withdrawal
+/// or if its result isn't sent to a fixed destination
+fn parse_liquidity_order_datum(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<Option<LiquidityOrderDatum>, String> {
+    // identify operation: 2 = deposit, 3 = withdrawal
+    let details = &datum["fields"][4];
+    let operation = details["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse operation")?;
+    let parse_int = |value: &serde_json::Value| -> Result<u64, &str> {
+        value["int"].as_u64().ok_or("Failed to parse amount")
+    };
+    let order = match operation {
+        2 => {
+            // assets are a tuple of (policy id, asset name, amount) tuples
+            let assets = &details["fields"][0]["list"];
+            LiquidityOrder::Deposit(
+                parse_int(&assets[0]["list"][2])?,
+                parse_int(&assets[1]["list"][2])?,
+            )
+        }
+        3 => {
+            // LP tokens are a (policy id, asset name, amount) tuple
+            let lp = &details["fields"][0]["list"];
+            let parse_lp_item = |i: usize| -> Result<Vec<u8>, &str> {
+                let item = lp[i]["bytes"]
+                    .as_str()
+                    .ok_or("Failed to parse LP item")?
+                    .to_string();
+                hex::decode(item).map_err(|_e| "Failed to parse LP item")
+            };
+            LiquidityOrder::Withdrawal((parse_lp_item(0)?, parse_lp_item(1)?), parse_int(&lp[2])?)
+        }
+        _ => return Ok(None),
+    };
+    let destination = match parse_destination(datum, network_id)? {
+        Some(destination) => destination,
+        None => return Ok(None),
+    };
+
+    Ok(Some(LiquidityOrderDatum { order, destination }))
+}
+
+/// Returns None if the result is sent to the owner of the order (`Self` destination)
+/// since the owner can be a script or a multisig that isn't an address
+fn parse_destination(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<Option<cml_chain::address::Address>, String> {
+    // destination: 0 = fixed address, 1 = the owner of the order
+    let destination = &datum["fields"][3];
+    match destination["constructor"].as_u64() {
+        Some(0) => address_from_datum(&destination["fields"][0], network_id).map(Some),
+        Some(1) => Ok(None),
+        _ => Err("Failed to parse destination".to_string()),
+    }
+}
+
+/// Mainnet scripts, used unless the execution plan overrides them
+/// note: the pool validator is also the minting policy of pool NFTs and LP tokens
+pub fn mainnet_scripts() -> DexScripts {
+    DexScripts {
+        pool_script_hashes: vec![parse_script_hash(POOL_SCRIPT_HASH).unwrap()],
+        order_script_hashes: vec![parse_script_hash(ORDER_SCRIPT_HASH).unwrap()],
+        pool_nft_policy_id: Some(parse_script_hash(POOL_SCRIPT_HASH).unwrap()),
+        ..Default::default()
+    }
+}
+
+impl Dex for SundaeSwapV3 {
+    fn queue_mean_price(
+        &self,
+        queued_prices: &mut Vec<QueuedMeanPrice>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        if let Some((output, datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let datum = parse_pool_datum(&datum_to_json(datum)?)?;
+
+            let amount1 = get_asset_amount(output, &datum.asset_a)
+                .saturating_sub(reduce_ada_amount(&datum.asset_a, datum.protocol_fees));
+            let amount2 = get_asset_amount(output, &datum.asset_b)
+                .saturating_sub(reduce_ada_amount(&datum.asset_b, datum.protocol_fees));
+
+            queued_prices.push(QueuedMeanPrice {
+                tx_id,
+                address: output.address().to_raw_bytes().to_vec(),
+                dex_type: DexType::SundaeSwapV3,
+                asset1: datum.asset_a,
+                asset2: datum.asset_b,
+                amount1,
+                amount2,
+            });
+        }
+        Ok(())
+    }
+
+    fn queue_swap(
+        &self,
+        queued_swaps: &mut Vec<QueuedSwap>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+        multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        if let Some((main_output, main_datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let pool = parse_pool_datum(&datum_to_json(main_datum)?)?;
+            let network_id = main_output
+                .address()
+                .network_id()
+                .map_err(|_e| "Failed to parse network id")?;
+
+            let mut free_utxos: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx.outputs();
+
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
+            for (_input, input_datum) in filter_outputs_and_datums_by_hash(
+                &inputs,
+                &self.scripts.order_script_hashes,
+                &tx_witness.plutus_datums,
+            ) {
+                let input_datum = datum_to_json(&input_datum)?;
+                let order = match parse_swap_order_datum(&input_datum, network_id)? {
+                    Some(order) => order,
+                    None => {
+                        tracing::debug!("Operation is not a swap to a fixed destination");
+                        continue;
+                    }
+                };
+
+                // Get coresponding UTxO with result
+                let utxo_pos = free_utxos
+                    .iter()
+                    .position(|o| o.address() == order.destination)
+                    .ok_or("Failed to find utxo")?;
+                let utxo = free_utxos[utxo_pos].clone();
+                free_utxos.remove(utxo_pos);
+
+                // Get amount and direction
+                // note: the offered amount is exact so it's taken from the datum
+                let amount1;
+                let amount2;
+                let operation;
+                if order.offer_asset == pool.asset_a {
+                    amount1 = order.offer_amount;
+                    amount2 = get_asset_amount(&utxo, &pool.asset_b)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_b, ORDER_DEPOSIT_ADA));
+                    operation = Operation::Sell;
+                } else {
+                    amount1 = get_asset_amount(&utxo, &pool.asset_a)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_a, ORDER_DEPOSIT_ADA));
+                    amount2 = order.offer_amount;
+                    operation = Operation::Buy;
+                }
+                queued_swaps.push(QueuedSwap {
+                    tx_id,
+                    address: main_output.address().to_raw_bytes().to_vec(),
+                    dex_type: DexType::SundaeSwapV3,
+                    asset1: pool.asset_a.clone(),
+                    asset2: pool.asset_b.clone(),
+                    amount1,
+                    amount2,
+                    operation,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn queue_liquidity(
+        &self,
+        queued_liquidity: &mut Vec<QueuedLiquidity>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+        multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        if let Some((main_output, main_datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let pool = parse_pool_datum(&datum_to_json(main_datum)?)?;
+            let network_id = main_output
+                .address()
+                .network_id()
+                .map_err(|_e| "Failed to parse network id")?;
+
+            let lp_policy_id = self.scripts.lp_policy_id()?;
+            let mut free_utxos: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx.outputs();
+
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
+            for (_input, input_datum) in filter_outputs_and_datums_by_hash(
+                &inputs,
+                &self.scripts.order_script_hashes,
+                &tx_witness.plutus_datums,
+            ) {
+                let input_datum = datum_to_json(&input_datum)?;
+                let order = match parse_liquidity_order_datum(&input_datum, network_id)? {
+                    Some(order) => order,
+                    None => {
+                        tracing::debug!(
+                            "Operation is not a deposit or a withdrawal to a fixed destination"
+                        );
+                        continue;
+                    }
+                };
+
+                // Get coresponding UTxO with result
+                let utxo_pos = free_utxos
+                    .iter()
+                    .position(|o| o.address() == order.destination)
+                    .ok_or("Failed to find utxo")?;
+                let utxo = free_utxos[utxo_pos].clone();
+                free_utxos.remove(utxo_pos);
+
+                // Get amounts
+                // note: the offered amounts are exact so they are taken from the datum
+                let (amount1, amount2, (lp_asset, lp_amount), operation) = match order.order {
+                    LiquidityOrder::Deposit(amount_a, amount_b) => (
+                        amount_a,
+                        amount_b,
+                        get_lp_asset(&utxo, lp_policy_id).ok_or("Failed to find LP tokens")?,
+                        LiquidityOperation::Deposit,
+                    ),
+                    LiquidityOrder::Withdrawal(lp_asset, lp_amount) => (
+                        get_asset_amount(&utxo, &pool.asset_a)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_a, ORDER_DEPOSIT_ADA)),
+                        get_asset_amount(&utxo, &pool.asset_b)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_b, ORDER_DEPOSIT_ADA)),
+                        (lp_asset, lp_amount),
+                        LiquidityOperation::Withdrawal,
+                    ),
+                };
+                queued_liquidity.push(QueuedLiquidity {
+                    tx_id,
+                    address: main_output.address().to_raw_bytes().to_vec(),
+                    owner: order.destination.to_raw_bytes(),
+                    dex_type: DexType::SundaeSwapV3,
+                    asset1: pool.asset_a.clone(),
+                    asset2: pool.asset_b.clone(),
+                    amount1,
+                    amount2,
+                    lp_asset,
+                    lp_amount,
+                    operation,
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mainnet_scripts;
+    use crate::multiera::dex::common::test_utils::{spent_outputs_map, tx_body};
+    use crate::multiera::dex::common::{Dex, SundaeSwapV3};
+    use cml_chain::transaction::TransactionWitnessSet;
+    use entity::dex_liquidity::Operation as LiquidityOperation;
+    use entity::dex_swap::Operation;
+
+    const POOL_INPUT: &str = "a300581d71e0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312b01821a3bc890c0a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e4441451a02faf080028201d8185861d8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3c9f9f4040ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e444145ffff1a3b9aca00181e181ed87a80001a002dc6c0ff";
+    const SWAP_ORDER_INPUT: &str = "a300581d71fa6a58bbe2d0ff05534431c8e2f0ef2cbdc1602a8456e4b13c8f3077011a01ab3f00028201d81858ddd8799fd8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3cffd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cff1a000f4240d8799fd8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87980ffd87a9f9f40401a017d7840ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e4441451864ffffd87980ff";
+
+    fn sundae() -> Option<(Vec<u8>, Vec<u8>)> {
+        Some((
+            hex::decode("9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77").unwrap(),
+            hex::decode("53554e444145").unwrap(),
+        ))
+    }
+
+    #[test]
+    fn swap_transaction() {
+        // 25 ADA sold for 1.2 SUNDAE from the ADA / SUNDAE pool
+        let tx = tx_body("a30082825820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00825820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb010182a300581d71e0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312b01821a3d460900a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e4441451a02e8a100028201d8185861d8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3c9f9f4040ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e444145ffff1a3b9aca00181e181ed87a80001a002dc6c0ffa200583901f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc412101821a001e8480a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e4441451a00124f80021a00030d40");
+        let spent_outputs = spent_outputs_map(&tx, &[POOL_INPUT, SWAP_ORDER_INPUT]);
+        let dex = SundaeSwapV3 {
+            scripts: mainnet_scripts(),
+        };
+
+        // the protocol fees held by the pool aren't part of the reserves
+        let mut queued_prices = vec![];
+        dex.queue_mean_price(&mut queued_prices, &tx, &TransactionWitnessSet::new(), 0)
+            .unwrap();
+        assert_eq!(queued_prices.len(), 1);
+        assert_eq!(queued_prices[0].asset1, None);
+        assert_eq!(queued_prices[0].asset2, sundae());
+        assert_eq!(queued_prices[0].amount1, 1_025_000_000);
+        assert_eq!(queued_prices[0].amount2, 48_800_000);
+
+        let mut queued_swaps = vec![];
+        dex.queue_swap(
+            &mut queued_swaps,
+            &tx,
+            &TransactionWitnessSet::new(),
+            0,
+            &spent_outputs,
+        )
+        .unwrap();
+        assert_eq!(queued_swaps.len(), 1);
+        let swap = &queued_swaps[0];
+        assert_eq!(
+            hex::encode(&swap.address),
+            "71e0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312b"
+        );
+        assert_eq!(swap.asset1, None);
+        assert_eq!(swap.asset2, sundae());
+        assert_eq!(swap.amount1, 25_000_000);
+        assert_eq!(swap.amount2, 1_200_000);
+        assert!(swap.operation == Operation::Sell);
+    }
+
+    #[test]
+    fn swap_transaction_with_self_destination() {
+        // the first order sends its result back to its owner, which isn't tracked,
+        // and the second one to a fixed address
+        let tx = tx_body("a30083825820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00825820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb01825820cccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc000183a300581d71e0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312b01821a3ec38140a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e4441451a02d714d0028201d8185861d8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3c9f9f4040ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e444145ffff1a3b9aca00181e181ed87a80001a002dc6c0ffa200581d61f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c01821a001e8480a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e4441451a00118c30a200583901f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc412101821a001e8480a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e4441451a00124f80021a00030d40");
+        let spent_outputs = spent_outputs_map(
+            &tx,
+            &[POOL_INPUT, "a300581d71fa6a58bbe2d0ff05534431c8e2f0ef2cbdc1602a8456e4b13c8f3077011a01ab3f00028201d8185889d8799fd8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3cffd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cff1a000f4240d87a80d87a9f9f40401a017d7840ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e4441451864ffffd87980ff", SWAP_ORDER_INPUT],
+        );
+        let dex = SundaeSwapV3 {
+            scripts: mainnet_scripts(),
+        };
+
+        let mut queued_swaps = vec![];
+        dex.queue_swap(
+            &mut queued_swaps,
+            &tx,
+            &TransactionWitnessSet::new(),
+            0,
+            &spent_outputs,
+        )
+        .unwrap();
+        assert_eq!(queued_swaps.len(), 1);
+        assert_eq!(queued_swaps[0].amount1, 25_000_000);
+        assert_eq!(queued_swaps[0].amount2, 1_200_000);
+        assert!(queued_swaps[0].operation == Operation::Sell);
+    }
+
+    #[test]
+    fn deposit_transaction_with_self_destination() {
+        // both orders deposit 10 ADA and 5000 SUNDAE, the first one sends the LP tokens
+        // back to its owner, which isn't tracked, and the second one to a fixed address
+        let tx = tx_body("a30083825820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00825820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb01825820cccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc000183a300581d71e0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312b01821a3cf9bdc0a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e4441451a02fb1790028201d8185861d8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3c9f9f4040ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e444145ffff1a3b9aca00181e181ed87a80001a002dc6c0ffa200581d61f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c01821a001e8480a1581ce0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312ba1581c0014df10ba228444515fbefd2c8725338e49589f206c7f18a33e002b1a00036976a200583901f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc412101821a001e8480a1581ce0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312ba1581c0014df10ba228444515fbefd2c8725338e49589f206c7f18a33e002b1a00036976021a00030d40");
+        let spent_outputs = spent_outputs_map(
+            &tx,
+            &[POOL_INPUT, "a300581d71fa6a58bbe2d0ff05534431c8e2f0ef2cbdc1602a8456e4b13c8f307701821a00c65d40a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e444145191388028201d818588cd8799fd8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3cffd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cff1a000f4240d87a80d87b9f9f9f40401a00989680ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e444145191388ffffffd87980ff", "a300581d71fa6a58bbe2d0ff05534431c8e2f0ef2cbdc1602a8456e4b13c8f307701821a00c65d40a1581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d77a14653554e444145191388028201d81858e0d8799fd8799f581cba228444515fbefd2c8725338e49589f206c7f18a33e002b157aac3cffd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cff1a000f4240d8799fd8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87980ffd87b9f9f9f40401a00989680ff9f581c9a9693a9a37912a5097918f97918d15240c92ab729a0b7c4aa144d774653554e444145191388ffffffd87980ff"],
+        );
+        let dex = SundaeSwapV3 {
+            scripts: mainnet_scripts(),
+        };
+
+        let mut queued_liquidity = vec![];
+        dex.queue_liquidity(
+            &mut queued_liquidity,
+            &tx,
+            &TransactionWitnessSet::new(),
+            0,
+            &spent_outputs,
+        )
+        .unwrap();
+        assert_eq!(queued_liquidity.len(), 1);
+        let liquidity = &queued_liquidity[0];
+        assert_eq!(
+            hex::encode(&liquidity.owner),
+            "01f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121"
+        );
+        assert_eq!(liquidity.asset1, None);
+        assert_eq!(liquidity.asset2, sundae());
+        assert_eq!(liquidity.amount1, 10_000_000);
+        assert_eq!(liquidity.amount2, 5000);
+        assert_eq!(
+            liquidity.lp_asset,
+            (
+                hex::decode("e0302560ced2fdcbfcb2602697df970cd0d6a38f94b32703f51c312b").unwrap(),
+                hex::decode("0014df10ba228444515fbefd2c8725338e49589f206c7f18a33e002b").unwrap()
+            )
+        );
+        assert_eq!(liquidity.lp_amount, 223_606);
+        assert_eq!(liquidity.operation, LiquidityOperation::Deposit);
+    }
+}
@@ -2,18 +2,16 @@ use cml_chain::byron::ByronTxOut;
 use cml_chain::plutus::LegacyRedeemer;
 use cml_core::serialization::{FromBytes, Serialize};
 use cml_crypto::RawBytesEncoding;
-use cml_multi_era::utils::MultiEraTransactionOutput;
 use std::collections::BTreeMap;
 
 use entity::block::EraValue;
 use sea_orm::DbErr;
 
 use super::common::{
-    build_asset, filter_outputs_and_datums_by_hash, reduce_ada_amount, Dex, DexType,
-    QueuedMeanPrice, QueuedSwap, WingRidersV1,
+    build_asset, filter_outputs_and_datums_by_hash, get_spent_outputs, parse_script_hash,
+    reduce_ada_amount, Dex, DexScripts, DexType, QueuedMeanPrice, QueuedSwap, WingRidersV1,
 };
 use crate::multiera::dex::common::datum_to_json;
-use crate::multiera::utils::common::output_from_bytes;
 use crate::{
     era_common::OutputWithTxData,
     multiera::utils::common::{get_asset_amount, get_plutus_datum_for_output},
@@ -25,6 +23,15 @@ const POOL_FIXED_ADA: u64 = 3_000_000; // every pool UTXO holds this amount of A
 const SWAP_IN_ADA: u64 = 4_000_000; // oil ADA + agent fee
 const SWAP_OUT_ADA: u64 = 2_000_000; // oil ADA
 
+/// Mainnet scripts, used unless the execution plan overrides them
+/// note: requests are found through the redeemers of the pool so only the pool script is needed
+pub fn mainnet_scripts() -> DexScripts {
+    DexScripts {
+        pool_script_hashes: vec![parse_script_hash(POOL_SCRIPT_HASH).unwrap()],
+        ..Default::default()
+    }
+}
+
 impl Dex for WingRidersV1 {
     fn queue_mean_price(
         &self,
@@ -36,7 +43,7 @@ impl Dex for WingRidersV1 {
         // Note: there should be at most one pool output
         if let Some((output, datum)) = filter_outputs_and_datums_by_hash(
             &tx.outputs(),
-            &[POOL_SCRIPT_HASH],
+            &self.scripts.pool_script_hashes,
             &tx_witness.plutus_datums,
         )
         .first()
@@ -91,7 +98,7 @@ impl Dex for WingRidersV1 {
         // Note: there should be at most one pool output
         if let Some((pool_output, _)) = filter_outputs_and_datums_by_hash(
             &tx.outputs(),
-            &[POOL_SCRIPT_HASH],
+            &self.scripts.pool_script_hashes,
             &tx_witness.plutus_datums,
         )
         .first()
@@ -144,16 +151,7 @@ impl Dex for WingRidersV1 {
                 .as_i64()
                 .ok_or("Failed to parse main transaction")? as usize;
             // Restore inputs
-            let inputs: Vec<MultiEraTransactionOutput> = tx
-                .inputs()
-                .iter()
-                .map(|i| {
-                    let output = &multiera_used_inputs_to_outputs_map
-                        [&i.hash().unwrap().to_raw_bytes().to_vec()]
-                        [&(i.index().unwrap() as i64)];
-                    output_from_bytes(output).unwrap()
-                })
-                .collect::<Vec<_>>();
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
             // Zip outputs with redemeer index
             for (output, redeemer) in tx.outputs().iter().skip(1).zip(redeemer_map) {
                 // pair input with output
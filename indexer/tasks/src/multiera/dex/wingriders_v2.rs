@@ -0,0 +1,434 @@
+use cml_crypto::RawBytesEncoding;
+use std::collections::BTreeMap;
+
+use super::common::{
+    address_from_datum, build_asset, filter_outputs_and_datums_by_hash, get_lp_asset,
+    get_spent_outputs, parse_script_hash, reduce_ada_amount, Dex, DexScripts, DexType,
+    QueuedLiquidity, QueuedMeanPrice, QueuedSwap, WingRidersV2,
+};
+use crate::multiera::dex::common::datum_to_json;
+use crate::types::AssetPair;
+use crate::{era_common::OutputWithTxData, multiera::utils::common::get_asset_amount};
+use entity::dex_liquidity::Operation as LiquidityOperation;
+use entity::dex_swap::Operation;
+
+pub const POOL_SCRIPT_HASH: &str = "6b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b69";
+pub const REQUEST_SCRIPT_HASH: &str = "c134d839a64a5dfb9b155869ef3f34280751a622f69958baa8ffd29c";
+pub const POOL_FIXED_ADA: u64 = 3_000_000; // every pool UTXO holds this amount of ADA
+
+/// Content of a pool datum needed to compute prices
+#[derive(Debug, PartialEq, Eq)]
+struct PoolDatum {
+    asset_a: AssetPair,
+    asset_b: AssetPair,
+    agent_fee: u64,
+    // note: treasuries (regular, project & reserve) are kept in the pool UTxO
+    // but they aren't part of the liquidity
+    treasury_a: u64,
+    treasury_b: u64,
+}
+
+/// Content of a swap request datum needed to find the swap result
+#[derive(Debug, PartialEq)]
+struct SwapRequestDatum {
+    a_to_b: bool,
+    oil_ada: u64,
+    beneficiary: cml_chain::address::Address,
+}
+
+/// Content of a liquidity request datum needed to find the LP tokens
+#[derive(Debug, PartialEq)]
+struct LiquidityRequestDatum {
+    operation: LiquidityOperation,
+    oil_ada: u64,
+    beneficiary: cml_chain::address::Address,
+}
+
+fn parse_pool_datum(datum: &serde_json::Value) -> Result<PoolDatum, String> {
+    let parse_asset_item = |i| -> Result<Vec<u8>, &str> {
+        let item = datum["fields"][i]["bytes"]
+            .as_str()
+            .ok_or("Failed to parse asset item")?
+            .to_string();
+        hex::decode(item).map_err(|_e| "Failed to parse asset item")
+    };
+    let parse_int = |i| -> Result<u64, &str> {
+        datum["fields"][i]["int"]
+            .as_u64()
+            .ok_or("Failed to parse pool datum")
+    };
+
+    Ok(PoolDatum {
+        asset_a: build_asset(parse_asset_item(1)?, parse_asset_item(2)?),
+        asset_b: build_asset(parse_asset_item(3)?, parse_asset_item(4)?),
+        agent_fee: parse_int(10)?,
+        treasury_a: parse_int(12)? + parse_int(14)? + parse_int(16)?,
+        treasury_b: parse_int(13)? + parse_int(15)? + parse_int(17)?,
+    })
+}
+
+/// Returns None if the request isn't a swap (ex: add or withdraw liquidity)
+fn parse_swap_request_datum(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<Option<SwapRequestDatum>, String> {
+    // identify operation: 0 = swap
+    let action = &datum["fields"][10];
+    let operation = action["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse operation")?;
+    if operation != 0 {
+        return Ok(None);
+    }
+    // direction: 0 = A to B, 1 = B to A
+    let direction = action["fields"][0]["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse direction")?;
+
+    Ok(Some(SwapRequestDatum {
+        a_to_b: direction == 0,
+        oil_ada: datum["fields"][0]["int"]
+            .as_u64()
+            .ok_or("Failed to parse oil ADA")?,
+        beneficiary: address_from_datum(&datum["fields"][1], network_id)?,
+    }))
+}
+
+/// Returns None if the request isn't a deposit or a withdrawal (ex: swap)
+fn parse_liquidity_request_datum(
+    datum: &serde_json::Value,
+    network_id: u8,
+) -> Result<Option<LiquidityRequestDatum>, String> {
+    // identify operation: 1 = add liquidity, 2 = withdraw liquidity
+    let operation = match datum["fields"][10]["constructor"]
+        .as_u64()
+        .ok_or("Failed to parse operation")?
+    {
+        1 => LiquidityOperation::Deposit,
+        2 => LiquidityOperation::Withdrawal,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(LiquidityRequestDatum {
+        operation,
+        oil_ada: datum["fields"][0]["int"]
+            .as_u64()
+            .ok_or("Failed to parse oil ADA")?,
+        beneficiary: address_from_datum(&datum["fields"][1], network_id)?,
+    }))
+}
+
+/// Mainnet scripts, used unless the execution plan overrides them
+/// note: the pool validator is also the minting policy of pool NFTs and LP tokens
+pub fn mainnet_scripts() -> DexScripts {
+    DexScripts {
+        pool_script_hashes: vec![parse_script_hash(POOL_SCRIPT_HASH).unwrap()],
+        order_script_hashes: vec![parse_script_hash(REQUEST_SCRIPT_HASH).unwrap()],
+        pool_nft_policy_id: Some(parse_script_hash(POOL_SCRIPT_HASH).unwrap()),
+        ..Default::default()
+    }
+}
+
+impl Dex for WingRidersV2 {
+    fn queue_mean_price(
+        &self,
+        queued_prices: &mut Vec<QueuedMeanPrice>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        if let Some((output, datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let datum = parse_pool_datum(&datum_to_json(datum)?)?;
+
+            let amount1 = get_asset_amount(output, &datum.asset_a)
+                .saturating_sub(datum.treasury_a)
+                .saturating_sub(reduce_ada_amount(&datum.asset_a, POOL_FIXED_ADA));
+            let amount2 = get_asset_amount(output, &datum.asset_b)
+                .saturating_sub(datum.treasury_b)
+                .saturating_sub(reduce_ada_amount(&datum.asset_b, POOL_FIXED_ADA));
+
+            queued_prices.push(QueuedMeanPrice {
+                tx_id,
+                address: output.address().to_raw_bytes().to_vec(),
+                dex_type: DexType::WingRidersV2,
+                asset1: datum.asset_a,
+                asset2: datum.asset_b,
+                amount1,
+                amount2,
+            });
+        }
+        Ok(())
+    }
+
+    fn queue_swap(
+        &self,
+        queued_swaps: &mut Vec<QueuedSwap>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+        multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        if let Some((pool_output, pool_datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let pool = parse_pool_datum(&datum_to_json(pool_datum)?)?;
+            let network_id = pool_output
+                .address()
+                .network_id()
+                .map_err(|_e| "Failed to parse network id")?;
+
+            let mut free_utxos: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx.outputs();
+
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
+            for (input, input_datum) in filter_outputs_and_datums_by_hash(
+                &inputs,
+                &self.scripts.order_script_hashes,
+                &tx_witness.plutus_datums,
+            ) {
+                let input_datum = datum_to_json(&input_datum)?;
+                let request = match parse_swap_request_datum(&input_datum, network_id)? {
+                    Some(request) => request,
+                    None => {
+                        tracing::debug!("Operation is not a swap");
+                        continue;
+                    }
+                };
+
+                // Get coresponding UTxO with result
+                let utxo_pos = free_utxos
+                    .iter()
+                    .position(|o| o.address() == request.beneficiary)
+                    .ok_or("Failed to find utxo")?;
+                let utxo = free_utxos[utxo_pos].clone();
+                free_utxos.remove(utxo_pos);
+
+                // Get amount and direction
+                let swap_in_ada = request.oil_ada + pool.agent_fee;
+                let amount1;
+                let amount2;
+                let operation;
+                if request.a_to_b {
+                    amount1 = get_asset_amount(&input, &pool.asset_a)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_a, swap_in_ada));
+                    amount2 = get_asset_amount(&utxo, &pool.asset_b)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_b, request.oil_ada));
+                    operation = Operation::Sell;
+                } else {
+                    amount1 = get_asset_amount(&utxo, &pool.asset_a)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_a, request.oil_ada));
+                    amount2 = get_asset_amount(&input, &pool.asset_b)
+                        .saturating_sub(reduce_ada_amount(&pool.asset_b, swap_in_ada));
+                    operation = Operation::Buy;
+                }
+                queued_swaps.push(QueuedSwap {
+                    tx_id,
+                    address: pool_output.address().to_raw_bytes().to_vec(),
+                    dex_type: DexType::WingRidersV2,
+                    asset1: pool.asset_a.clone(),
+                    asset2: pool.asset_b.clone(),
+                    amount1,
+                    amount2,
+                    operation,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn queue_liquidity(
+        &self,
+        queued_liquidity: &mut Vec<QueuedLiquidity>,
+        tx: &cml_multi_era::MultiEraTransactionBody,
+        tx_witness: &cml_chain::transaction::TransactionWitnessSet,
+        tx_id: i64,
+        multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+    ) -> Result<(), String> {
+        // Note: there should be at most one pool output
+        if let Some((pool_output, pool_datum)) = filter_outputs_and_datums_by_hash(
+            &tx.outputs(),
+            &self.scripts.pool_script_hashes,
+            &tx_witness.plutus_datums,
+        )
+        .first()
+        {
+            let pool = parse_pool_datum(&datum_to_json(pool_datum)?)?;
+            let network_id = pool_output
+                .address()
+                .network_id()
+                .map_err(|_e| "Failed to parse network id")?;
+
+            let lp_policy_id = self.scripts.lp_policy_id()?;
+            let mut free_utxos: Vec<cml_multi_era::utils::MultiEraTransactionOutput> = tx.outputs();
+
+            let inputs = get_spent_outputs(tx, multiera_used_inputs_to_outputs_map)?;
+            for (input, input_datum) in filter_outputs_and_datums_by_hash(
+                &inputs,
+                &self.scripts.order_script_hashes,
+                &tx_witness.plutus_datums,
+            ) {
+                let input_datum = datum_to_json(&input_datum)?;
+                let request = match parse_liquidity_request_datum(&input_datum, network_id)? {
+                    Some(request) => request,
+                    None => {
+                        tracing::debug!("Operation is not a deposit or a withdrawal");
+                        continue;
+                    }
+                };
+
+                // Get coresponding UTxO with result
+                let utxo_pos = free_utxos
+                    .iter()
+                    .position(|o| o.address() == request.beneficiary)
+                    .ok_or("Failed to find utxo")?;
+                let utxo = free_utxos[utxo_pos].clone();
+                free_utxos.remove(utxo_pos);
+
+                // Get amounts
+                let request_in_ada = request.oil_ada + pool.agent_fee;
+                let (amount1, amount2, (lp_asset, lp_amount)) = match request.operation {
+                    LiquidityOperation::Deposit => (
+                        get_asset_amount(&input, &pool.asset_a)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_a, request_in_ada)),
+                        get_asset_amount(&input, &pool.asset_b)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_b, request_in_ada)),
+                        get_lp_asset(&utxo, lp_policy_id).ok_or("Failed to find LP tokens")?,
+                    ),
+                    LiquidityOperation::Withdrawal => (
+                        get_asset_amount(&utxo, &pool.asset_a)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_a, request.oil_ada)),
+                        get_asset_amount(&utxo, &pool.asset_b)
+                            .saturating_sub(reduce_ada_amount(&pool.asset_b, request.oil_ada)),
+                        get_lp_asset(&input, lp_policy_id).ok_or("Failed to find LP tokens")?,
+                    ),
+                };
+                queued_liquidity.push(QueuedLiquidity {
+                    tx_id,
+                    address: pool_output.address().to_raw_bytes().to_vec(),
+                    owner: request.beneficiary.to_raw_bytes(),
+                    dex_type: DexType::WingRidersV2,
+                    asset1: pool.asset_a.clone(),
+                    asset2: pool.asset_b.clone(),
+                    amount1,
+                    amount2,
+                    lp_asset,
+                    lp_amount,
+                    operation: request.operation,
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mainnet_scripts;
+    use crate::multiera::dex::common::test_utils::{spent_outputs_map, tx_body};
+    use crate::multiera::dex::common::{Dex, WingRidersV2};
+    use cml_chain::transaction::TransactionWitnessSet;
+    use entity::dex_liquidity::Operation as LiquidityOperation;
+    use entity::dex_swap::Operation;
+
+    const POOL_INPUT: &str = "a300581d716b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b6901821a3bdf7420a2581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d5073a14a57696e675269646572731a03211a08581c6b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b69a1414c01028201d818587ad8799f581cc134d839a64a5dfb9b155869ef3f34280751a622f69958baa8ffd29c4040581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d50734a57696e6752696465727318230f00001927101a001e84801b0000018bcfe568001a0016e3601a002625a000000000d87a80d87a80d87980ff";
+
+    fn wrt() -> Option<(Vec<u8>, Vec<u8>)> {
+        Some((
+            hex::decode("c0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d5073").unwrap(),
+            hex::decode("57696e67526964657273").unwrap(),
+        ))
+    }
+
+    #[test]
+    fn swap_transaction() {
+        // 10 ADA sold for 1000 WRT from the ADA / WRT pool,
+        // the request also holds the oil ADA and the agent fee
+        let tx = tx_body("a30082825820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00825820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb010182a300581d716b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b6901821a3c780aa0a2581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d5073a14a57696e675269646572731a03211620581c6b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b69a1414c01028201d818587ad8799f581cc134d839a64a5dfb9b155869ef3f34280751a622f69958baa8ffd29c4040581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d50734a57696e6752696465727318230f00001927101a001e84801b0000018bcfe568001a0016e3601a002625a000000000d87a80d87a80d87980ffa200583901f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc412101821a001e8480a1581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d5073a14a57696e675269646572731903e8021a00030d40");
+        let spent_outputs = spent_outputs_map(&tx, &[POOL_INPUT, "a300581d71c134d839a64a5dfb9b155869ef3f34280751a622f69958baa8ffd29c011a00d59f80028201d81858efd8799f1a001e8480d8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87a80d879801b0000018bcfe568004040581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d50734a57696e67526964657273d8799fd879801903e8ff0101ff"]);
+        let dex = WingRidersV2 {
+            scripts: mainnet_scripts(),
+        };
+
+        // the treasuries and the fixed ADA held by the pool aren't part of the reserves
+        let mut queued_prices = vec![];
+        dex.queue_mean_price(&mut queued_prices, &tx, &TransactionWitnessSet::new(), 0)
+            .unwrap();
+        assert_eq!(queued_prices.len(), 1);
+        assert_eq!(queued_prices[0].asset1, None);
+        assert_eq!(queued_prices[0].asset2, wrt());
+        assert_eq!(queued_prices[0].amount1, 1_010_000_000);
+        assert_eq!(queued_prices[0].amount2, 50_000_000);
+
+        let mut queued_swaps = vec![];
+        dex.queue_swap(
+            &mut queued_swaps,
+            &tx,
+            &TransactionWitnessSet::new(),
+            0,
+            &spent_outputs,
+        )
+        .unwrap();
+        assert_eq!(queued_swaps.len(), 1);
+        let swap = &queued_swaps[0];
+        assert_eq!(
+            hex::encode(&swap.address),
+            "716b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b69"
+        );
+        assert_eq!(swap.asset1, None);
+        assert_eq!(swap.asset2, wrt());
+        assert_eq!(swap.amount1, 10_000_000);
+        assert_eq!(swap.amount2, 1000);
+        assert!(swap.operation == Operation::Sell);
+    }
+
+    #[test]
+    fn deposit_transaction() {
+        // 10 ADA and 5000 WRT deposited, the request also holds the oil ADA and the agent fee
+        let tx = tx_body("a30082825820aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00825820bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb010182a300581d716b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b6901821a3c780aa0a2581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d5073a14a57696e675269646572731a03212d90581c6b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b69a1414c01028201d818587ad8799f581cc134d839a64a5dfb9b155869ef3f34280751a622f69958baa8ffd29c4040581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d50734a57696e6752696465727318230f00001927101a001e84801b0000018bcfe568001a0016e3601a002625a000000000d87a80d87a80d87980ffa200583901f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc412101821a001e8480a1581c6b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b69a158202b4a1b3c0d9f8e7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a1a00036976021a00030d40");
+        let spent_outputs = spent_outputs_map(&tx, &[POOL_INPUT, "a300581d71c134d839a64a5dfb9b155869ef3f34280751a622f69958baa8ffd29c01821a00d59f80a1581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d5073a14a57696e67526964657273191388028201d81858ecd8799f1a001e8480d8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd8799fd8799f581cf5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4cffd8799fd8799fd8799f581c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121ffffffffd87a80d879801b0000018bcfe568004040581cc0ee29a85b13209423b10447d3c2e6a50641a15c57770e27cb9d50734a57696e67526964657273d87a9f1903e8ff0101ff"]);
+        let dex = WingRidersV2 {
+            scripts: mainnet_scripts(),
+        };
+
+        let mut queued_liquidity = vec![];
+        dex.queue_liquidity(
+            &mut queued_liquidity,
+            &tx,
+            &TransactionWitnessSet::new(),
+            0,
+            &spent_outputs,
+        )
+        .unwrap();
+        assert_eq!(queued_liquidity.len(), 1);
+        let liquidity = &queued_liquidity[0];
+        assert_eq!(
+            hex::encode(&liquidity.owner),
+            "01f5808c2c990d86da54bfc97d89cee6efa20cd8461616359478d96b4c2ffadbb87144e875749122e0bbb9f535eeaa7f5660c6c4a91bcc4121"
+        );
+        assert_eq!(liquidity.asset1, None);
+        assert_eq!(liquidity.asset2, wrt());
+        assert_eq!(liquidity.amount1, 10_000_000);
+        assert_eq!(liquidity.amount2, 5000);
+        assert_eq!(
+            liquidity.lp_asset,
+            (
+                hex::decode("6b9c456aa650cb808a9ab54326e039d5235ed69f069c9664a8fe5b69").unwrap(),
+                hex::decode("2b4a1b3c0d9f8e7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a")
+                    .unwrap()
+            )
+        );
+        assert_eq!(liquidity.lp_amount, 223_606);
+        assert_eq!(liquidity.operation, LiquidityOperation::Deposit);
+    }
+}
@@ -4,26 +4,46 @@ pub mod multiera_address_credential_relations;
 pub mod multiera_address_delegation;
 pub mod multiera_asset_mint;
 pub mod multiera_asset_utxo;
+pub mod multiera_balances;
 pub mod multiera_block;
 pub mod multiera_cip25entry;
+pub mod multiera_committee_authorization;
 pub mod multiera_datum;
+pub mod multiera_dex_candles;
 pub mod multiera_drep_delegation;
+pub mod multiera_drep_registration;
 pub mod multiera_executor;
+pub mod multiera_governance_proposals;
 pub mod multiera_governance_voting;
 pub mod multiera_metadata;
 pub mod multiera_minswap_v1_mean_price;
 pub mod multiera_minswap_v1_swap;
+pub mod multiera_minswap_v2_liquidity;
+pub mod multiera_minswap_v2_mean_price;
+pub mod multiera_minswap_v2_swap;
+pub mod multiera_pool_registration;
 pub mod multiera_projected_nft;
+pub mod multiera_redeemers;
 pub mod multiera_reference_inputs;
+pub mod multiera_scripts;
 pub mod multiera_stake_credentials;
+pub mod multiera_stake_snapshot;
 pub mod multiera_sundaeswap_v1_mean_price;
 pub mod multiera_sundaeswap_v1_swap;
+pub mod multiera_sundaeswap_v3_liquidity;
+pub mod multiera_sundaeswap_v3_mean_price;
+pub mod multiera_sundaeswap_v3_swap;
 pub mod multiera_tx_credential_relations;
 pub mod multiera_txs;
+pub mod multiera_unspent_outputs;
 pub mod multiera_unused_input;
 pub mod multiera_used_inputs;
 pub mod multiera_used_outputs;
 pub mod multiera_wingriders_v1_mean_price;
 pub mod multiera_wingriders_v1_swap;
+pub mod multiera_wingriders_v2_liquidity;
+pub mod multiera_wingriders_v2_mean_price;
+pub mod multiera_wingriders_v2_swap;
+pub mod multiera_withdrawals;
 pub mod relation_map;
 pub mod utils;
@@ -1,4 +1,5 @@
 use crate::{
+    multiera::multiera_pool_registration::MultieraPoolRegistrationTask,
     multiera::multiera_stake_credentials::MultieraStakeCredentialTask,
     types::{AddressCredentialRelationValue, TxCredentialRelationValue},
 };
@@ -26,7 +27,9 @@ carp_task! {
   configuration EmptyConfig;
   doc "Tracks stake delegation actions to pools.";
   era multiera;
+  version 2;
   dependencies [MultieraStakeCredentialTask];
+  optional_dependencies [MultieraPoolRegistrationTask];
   read [multiera_txs, multiera_stake_credential];
   write [];
   should_add_task |block, _properties| {
@@ -95,13 +98,12 @@ async fn handle(
 
                 let pool = pool.map(|pool| pool.to_raw_bytes().to_vec());
 
-                if let Some((previous, pool)) = previous_entry
-                    .as_ref()
-                    .and_then(|entry| entry.pool_credential.as_ref())
-                    .zip(pool.as_ref())
-                {
-                    // re-delegating shouldn't have any effect.
-                    if previous == pool {
+                if let (Some(previous), Some(pool)) = (previous_entry.as_ref(), pool.as_ref()) {
+                    // re-delegating shouldn't have any effect,
+                    // unless the pool retired in between (which removed the delegation)
+                    if previous.pool_credential.as_ref() == Some(pool)
+                        && !pool_retired_since(db_tx, pool, previous.tx_id).await?
+                    {
                         continue;
                     }
                 }
@@ -121,3 +123,20 @@ async fn handle(
 
     Ok(())
 }
+
+/// Whether the pool announced its retirement after the transaction `tx_id`
+/// note: this is only known if `MultieraPoolRegistrationTask` is part of the execution plan.
+/// A retirement cancelled by a new registration only adds a redundant delegation
+async fn pool_retired_since(
+    db_tx: &DatabaseTransaction,
+    pool: &[u8],
+    tx_id: i64,
+) -> Result<bool, DbErr> {
+    let retirement = PoolRegistration::find()
+        .filter(PoolRegistrationColumn::PoolCredential.eq(pool.to_vec()))
+        .filter(PoolRegistrationColumn::TxId.gt(tx_id))
+        .filter(PoolRegistrationColumn::RetiringEpoch.is_not_null())
+        .one(db_tx)
+        .await?;
+    Ok(retirement.is_some())
+}
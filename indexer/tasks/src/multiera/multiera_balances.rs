@@ -0,0 +1,677 @@
+//! Balances are updated incrementally from the outputs created and spent by each block.
+//! A balance also depends on all the blocks before the last one that changed it, so rolling back
+//! that block can't delete the balance: its `last_block_id` is set to null instead (see the foreign
+//! key of the migration) and the next block recomputes the balances without a last block from the UTxO set.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::multiera_address_credential_relations::MultieraAddressCredentialRelationTask;
+use super::multiera_asset_mint::MultieraAssetMintTask;
+use super::multiera_used_inputs::MultieraUsedInputTask;
+use super::multiera_used_outputs::MultieraOutputTask;
+use super::utils::common::{
+    asset_from_pair, output_from_bytes, output_from_payload, unspent_before,
+};
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::task_macro::*;
+use crate::era_common::OutputWithTxData;
+use crate::types::{AddressCredentialRelationValue, AssetPair};
+use cml_crypto::RawBytesEncoding;
+use cml_multi_era::utils::MultiEraTransactionOutput;
+use entity::block::EraValue;
+use entity::sea_orm::{
+    prelude::*, DatabaseTransaction, FromQueryResult, JoinType, QuerySelect, Set,
+};
+
+carp_task! {
+  name MultieraBalanceTask;
+  configuration EmptyConfig;
+  doc "Keeps the ADA and native asset balances of every address and stake credential up to date";
+  era multiera;
+  dependencies [
+    MultieraOutputTask,
+    MultieraUsedInputTask,
+    MultieraAddressCredentialRelationTask,
+    MultieraAssetMintTask
+  ];
+  read [multiera_block, multiera_txs, multiera_outputs, multiera_used_inputs_to_outputs_map];
+  write [];
+  should_add_task |_block, _properties| {
+    // balances may need to be recomputed after a rollback even if the block has no transaction
+    true
+  };
+  execute |previous_data, task| handle_balances(
+      task.db_tx,
+      previous_data.multiera_block.as_ref().unwrap(),
+      &previous_data.multiera_txs,
+      &previous_data.multiera_outputs,
+      &previous_data.multiera_used_inputs_to_outputs_map,
+  );
+  merge_result |_previous_data, _result| {
+  };
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum BalanceOwner {
+    Address(i64),
+    StakeCredential(i64),
+}
+
+impl BalanceOwner {
+    fn address_id(&self) -> Option<i64> {
+        match self {
+            BalanceOwner::Address(id) => Some(*id),
+            BalanceOwner::StakeCredential(_) => None,
+        }
+    }
+
+    fn credential_id(&self) -> Option<i64> {
+        match self {
+            BalanceOwner::Address(_) => None,
+            BalanceOwner::StakeCredential(id) => Some(*id),
+        }
+    }
+}
+
+/// signed so that it can hold the changes of a block
+pub(crate) type AssetAmounts = BTreeMap<AssetPair, i128>;
+
+fn add_output(amounts: &mut AssetAmounts, output: &MultiEraTransactionOutput, sign: i128) {
+    let value = output.amount();
+    *amounts.entry(None).or_default() += sign * value.coin as i128;
+    for (policy_id, assets) in value.multiasset.iter() {
+        for (asset_name, amount) in assets.iter() {
+            let pair = Some((
+                policy_id.to_raw_bytes().to_vec(),
+                asset_name.to_raw_bytes().to_vec(),
+            ));
+            *amounts.entry(pair).or_default() += sign * *amount as i128;
+        }
+    }
+}
+
+async fn handle_balances(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+    multiera_txs: &[TransactionModel],
+    multiera_outputs: &[TransactionOutputModel],
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Result<(), DbErr> {
+    // 1) Recompute the balances that lost changes in a rollback
+    // using the state before this block (which may already be indexed)
+    let first_tx_id = multiera_txs.first().map(|tx| tx.id);
+    recompute_rolled_back_balances(db_tx, database_block, first_tx_id).await?;
+
+    // 2) Changes of the block per address and stake credential
+    let era = EraValue::try_from(database_block.era)
+        .map_err(|_| DbErr::Custom(format!("unknown era {}", database_block.era)))?;
+    let mut changes = block_balance_changes(
+        db_tx,
+        era,
+        multiera_outputs,
+        multiera_used_inputs_to_outputs_map,
+    )
+    .await?;
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    // 3) Merge them with the balances of the previous blocks
+    let existing = get_balances(db_tx, changes.keys().copied()).await?;
+
+    // owners without any balance yet start from their UTxOs that were not seen by this task
+    // (ex: outputs of the genesis block, or if the task was added to an existing database)
+    let known_owners = existing
+        .keys()
+        .map(|(owner, _asset)| *owner)
+        .collect::<BTreeSet<_>>();
+    let new_owners = changes
+        .keys()
+        .filter(|owner| !known_owners.contains(owner))
+        .copied()
+        .collect::<BTreeSet<_>>();
+    for (owner, initial) in utxo_balances(db_tx, &new_owners, first_tx_id).await? {
+        let owner_changes = changes.entry(owner).or_default();
+        for (asset, amount) in initial {
+            *owner_changes.entry(asset).or_default() += amount;
+        }
+    }
+
+    let asset_ids = get_asset_ids(db_tx, &changes).await?;
+
+    let mut replaced_rows = vec![];
+    let mut new_balances = vec![];
+    for (owner, amounts) in changes {
+        for (asset, change) in amounts {
+            // ex: an output created and spent in the same block
+            if change == 0 {
+                continue;
+            }
+            let asset_id = asset.map(|pair| asset_ids[&pair]);
+            let (row_id, previous) = match existing.get(&(owner, asset_id)) {
+                Some((row_id, amount)) => (Some(*row_id), *amount as i128),
+                None => (None, 0),
+            };
+            let amount = u64::try_from(previous + change).map_err(|_| {
+                DbErr::Custom(format!(
+                    "invalid balance for {owner:?} and asset {asset_id:?}: {}",
+                    previous + change
+                ))
+            })?;
+            // zero balances are kept so that they can be restored after a rollback
+            if let Some(row_id) = row_id {
+                replaced_rows.push((owner, row_id));
+            }
+            new_balances.push((owner, asset_id, amount));
+        }
+    }
+    // the updated balances are replaced to write them in a few statements
+    delete_balances(db_tx, &replaced_rows).await?;
+    insert_balances(db_tx, new_balances, database_block).await?;
+
+    Ok(())
+}
+
+/// Changes of the balances of the addresses and stake credentials (of base addresses) touched by the block
+pub(crate) async fn block_balance_changes(
+    db_tx: &DatabaseTransaction,
+    era: EraValue,
+    multiera_outputs: &[TransactionOutputModel],
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Result<BTreeMap<BalanceOwner, AssetAmounts>, DbErr> {
+    let mut changes = BTreeMap::<BalanceOwner, AssetAmounts>::new();
+    for output in multiera_outputs.iter() {
+        add_output(
+            changes
+                .entry(BalanceOwner::Address(output.address_id))
+                .or_default(),
+            &output_from_payload(&output.payload, era)?,
+            1,
+        );
+    }
+    for output in multiera_used_inputs_to_outputs_map
+        .values()
+        .flat_map(|outputs| outputs.values())
+    {
+        add_output(
+            changes
+                .entry(BalanceOwner::Address(output.model.address_id))
+                .or_default(),
+            &output_from_bytes(output)?,
+            -1,
+        );
+    }
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+
+    // same changes for the stake credentials of the addresses
+    let stake_keys = AddressCredential::find()
+        .filter(
+            AddressCredentialColumn::AddressId
+                .is_in(changes.keys().filter_map(BalanceOwner::address_id)),
+        )
+        .filter(
+            AddressCredentialColumn::Relation
+                .eq(i32::from(AddressCredentialRelationValue::StakeKey)),
+        )
+        .all(db_tx)
+        .await?;
+    add_credential_changes(
+        &mut changes,
+        stake_keys
+            .iter()
+            .map(|stake_key| (stake_key.address_id, stake_key.credential_id)),
+    );
+
+    Ok(changes)
+}
+
+/// Adds the changes of the addresses to the changes of their stake credential
+fn add_credential_changes(
+    changes: &mut BTreeMap<BalanceOwner, AssetAmounts>,
+    stake_keys: impl Iterator<Item = (i64 /* address id */, i64 /* credential id */)>,
+) {
+    for (address_id, credential_id) in stake_keys {
+        let address_changes = changes[&BalanceOwner::Address(address_id)].clone();
+        let credential_changes = changes
+            .entry(BalanceOwner::StakeCredential(credential_id))
+            .or_default();
+        for (asset, amount) in address_changes {
+            *credential_changes.entry(asset).or_default() += amount;
+        }
+    }
+}
+
+async fn get_asset_ids(
+    db_tx: &DatabaseTransaction,
+    changes: &BTreeMap<BalanceOwner, AssetAmounts>,
+) -> Result<BTreeMap<(Vec<u8>, Vec<u8>), i64>, DbErr> {
+    let pairs = changes
+        .values()
+        .flat_map(|amounts| amounts.keys())
+        .flatten()
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    if pairs.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let assets = asset_from_pair(db_tx, &pairs.iter().cloned().collect::<Vec<_>>())
+        .await?
+        .into_iter()
+        .map(|asset| ((asset.policy_id, asset.asset_name), asset.id))
+        .collect::<BTreeMap<_, _>>();
+    // assets are always minted (so added to NativeAsset) before they can be in an output
+    let unknown = pairs.iter().find(|pair| !assets.contains_key(*pair));
+    if let Some((policy_id, asset_name)) = unknown {
+        return Err(DbErr::Custom(format!(
+            "unknown asset {}.{}",
+            hex::encode(policy_id),
+            hex::encode(asset_name)
+        )));
+    }
+    Ok(assets)
+}
+
+/// (owner, asset id) -> (row id, amount)
+async fn get_balances(
+    db_tx: &DatabaseTransaction,
+    owners: impl Iterator<Item = BalanceOwner>,
+) -> Result<BTreeMap<(BalanceOwner, Option<i64>), (i64, u64)>, DbErr> {
+    let owners = owners.collect::<Vec<_>>();
+    let addresses = owners
+        .iter()
+        .filter_map(BalanceOwner::address_id)
+        .collect::<Vec<_>>();
+    let credentials = owners
+        .iter()
+        .filter_map(BalanceOwner::credential_id)
+        .collect::<Vec<_>>();
+
+    let mut balances = BTreeMap::new();
+    if !addresses.is_empty() {
+        for balance in AddressBalance::find()
+            .filter(AddressBalanceColumn::AddressId.is_in(addresses))
+            .all(db_tx)
+            .await?
+        {
+            balances.insert(
+                (BalanceOwner::Address(balance.address_id), balance.asset_id),
+                (balance.id, balance.amount),
+            );
+        }
+    }
+    if !credentials.is_empty() {
+        for balance in StakeCredentialBalance::find()
+            .filter(StakeCredentialBalanceColumn::CredentialId.is_in(credentials))
+            .all(db_tx)
+            .await?
+        {
+            balances.insert(
+                (
+                    BalanceOwner::StakeCredential(balance.credential_id),
+                    balance.asset_id,
+                ),
+                (balance.id, balance.amount),
+            );
+        }
+    }
+    Ok(balances)
+}
+
+async fn delete_balances(
+    db_tx: &DatabaseTransaction,
+    rows: &[(BalanceOwner, i64 /* row id */)],
+) -> Result<(), DbErr> {
+    let address_rows = rows
+        .iter()
+        .filter(|(owner, _)| owner.address_id().is_some())
+        .map(|(_, row_id)| *row_id)
+        .collect::<Vec<_>>();
+    let credential_rows = rows
+        .iter()
+        .filter(|(owner, _)| owner.credential_id().is_some())
+        .map(|(_, row_id)| *row_id)
+        .collect::<Vec<_>>();
+
+    if !address_rows.is_empty() {
+        AddressBalance::delete_many()
+            .filter(AddressBalanceColumn::Id.is_in(address_rows))
+            .exec(db_tx)
+            .await?;
+    }
+    if !credential_rows.is_empty() {
+        StakeCredentialBalance::delete_many()
+            .filter(StakeCredentialBalanceColumn::Id.is_in(credential_rows))
+            .exec(db_tx)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn insert_balances(
+    db_tx: &DatabaseTransaction,
+    balances: Vec<(BalanceOwner, Option<i64>, u64)>,
+    database_block: &BlockModel,
+) -> Result<(), DbErr> {
+    let mut address_balances = vec![];
+    let mut credential_balances = vec![];
+    for (owner, asset_id, amount) in balances {
+        match owner {
+            BalanceOwner::Address(address_id) => address_balances.push(AddressBalanceActiveModel {
+                address_id: Set(address_id),
+                asset_id: Set(asset_id),
+                amount: Set(amount),
+                last_block_id: Set(Some(database_block.id)),
+                ..Default::default()
+            }),
+            BalanceOwner::StakeCredential(credential_id) => {
+                credential_balances.push(StakeCredentialBalanceActiveModel {
+                    credential_id: Set(credential_id),
+                    asset_id: Set(asset_id),
+                    amount: Set(amount),
+                    last_block_id: Set(Some(database_block.id)),
+                    ..Default::default()
+                })
+            }
+        }
+    }
+
+    if !address_balances.is_empty() {
+        AddressBalance::insert_many(address_balances)
+            .exec(db_tx)
+            .await?;
+    }
+    if !credential_balances.is_empty() {
+        StakeCredentialBalance::insert_many(credential_balances)
+            .exec(db_tx)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Balances whose last block got rolled back (see `last_block_id`) are rebuilt from the UTxO set
+async fn recompute_rolled_back_balances(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+    first_tx_id: Option<i64>,
+) -> Result<(), DbErr> {
+    let mut owners = BTreeSet::<BalanceOwner>::new();
+    owners.extend(
+        AddressBalance::find()
+            .filter(AddressBalanceColumn::LastBlockId.is_null())
+            .all(db_tx)
+            .await?
+            .into_iter()
+            .map(|balance| BalanceOwner::Address(balance.address_id)),
+    );
+    owners.extend(
+        StakeCredentialBalance::find()
+            .filter(StakeCredentialBalanceColumn::LastBlockId.is_null())
+            .all(db_tx)
+            .await?
+            .into_iter()
+            .map(|balance| BalanceOwner::StakeCredential(balance.credential_id)),
+    );
+    if owners.is_empty() {
+        return Ok(());
+    }
+
+    let balances = utxo_balances(db_tx, &owners, first_tx_id).await?;
+
+    // every asset of the owner is recomputed, so the old rows can be replaced
+    AddressBalance::delete_many()
+        .filter(
+            AddressBalanceColumn::AddressId
+                .is_in(owners.iter().filter_map(BalanceOwner::address_id)),
+        )
+        .exec(db_tx)
+        .await?;
+    StakeCredentialBalance::delete_many()
+        .filter(
+            StakeCredentialBalanceColumn::CredentialId
+                .is_in(owners.iter().filter_map(BalanceOwner::credential_id)),
+        )
+        .exec(db_tx)
+        .await?;
+
+    let asset_ids = get_asset_ids(db_tx, &balances).await?;
+    insert_balances(
+        db_tx,
+        recomputed_balances(balances, &asset_ids)?,
+        database_block,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Rows of the balances computed from the UTxO set
+fn recomputed_balances(
+    balances: BTreeMap<BalanceOwner, AssetAmounts>,
+    asset_ids: &BTreeMap<(Vec<u8>, Vec<u8>), i64>,
+) -> Result<Vec<(BalanceOwner, Option<i64>, u64)>, DbErr> {
+    let mut rows = vec![];
+    for (owner, amounts) in balances {
+        for (asset, amount) in amounts {
+            // spent assets are simply not in the UTxO set anymore
+            if amount == 0 {
+                continue;
+            }
+            let amount = u64::try_from(amount)
+                .map_err(|_| DbErr::Custom(format!("invalid balance for {owner:?}: {amount}")))?;
+            rows.push((owner, asset.map(|pair| asset_ids[&pair]), amount));
+        }
+    }
+    Ok(rows)
+}
+
+/// Balances of the owners computed from the UTxO set right before `first_tx_id`
+async fn utxo_balances(
+    db_tx: &DatabaseTransaction,
+    owners: &BTreeSet<BalanceOwner>,
+    first_tx_id: Option<i64>,
+) -> Result<BTreeMap<BalanceOwner, AssetAmounts>, DbErr> {
+    #[derive(FromQueryResult)]
+    pub struct OwnedOutput {
+        owner_id: i64,
+        payload: Vec<u8>,
+        era: i32,
+    }
+
+    let unspent_outputs = || {
+        let query = TransactionOutput::find()
+            .select_only()
+            .column(TransactionOutputColumn::Payload)
+            .column(BlockColumn::Era)
+            .join(
+                JoinType::InnerJoin,
+                TransactionOutputRelation::Transaction.def(),
+            )
+            .join(JoinType::InnerJoin, TransactionRelation::Block.def())
+            .join(
+                JoinType::LeftJoin,
+                TransactionOutputRelation::TransactionInput.def(),
+            )
+            .filter(unspent_before(first_tx_id));
+        match first_tx_id {
+            Some(first_tx_id) => query.filter(TransactionOutputColumn::TxId.lt(first_tx_id)),
+            None => query,
+        }
+    };
+
+    let addresses = owners
+        .iter()
+        .filter_map(BalanceOwner::address_id)
+        .collect::<Vec<_>>();
+    let credentials = owners
+        .iter()
+        .filter_map(BalanceOwner::credential_id)
+        .collect::<Vec<_>>();
+
+    let mut outputs = vec![];
+    if !addresses.is_empty() {
+        outputs.extend(
+            unspent_outputs()
+                .column_as(TransactionOutputColumn::AddressId, "owner_id")
+                .filter(TransactionOutputColumn::AddressId.is_in(addresses))
+                .into_model::<OwnedOutput>()
+                .all(db_tx)
+                .await?
+                .into_iter()
+                .map(|output| (BalanceOwner::Address(output.owner_id), output)),
+        );
+    }
+    if !credentials.is_empty() {
+        outputs.extend(
+            unspent_outputs()
+                .column_as(AddressCredentialColumn::CredentialId, "owner_id")
+                .join(
+                    JoinType::InnerJoin,
+                    TransactionOutputRelation::Address.def(),
+                )
+                .join(
+                    JoinType::InnerJoin,
+                    AddressCredentialRelation::Address.def().rev(),
+                )
+                .filter(AddressCredentialColumn::CredentialId.is_in(credentials))
+                .filter(
+                    AddressCredentialColumn::Relation
+                        .eq(i32::from(AddressCredentialRelationValue::StakeKey)),
+                )
+                .into_model::<OwnedOutput>()
+                .all(db_tx)
+                .await?
+                .into_iter()
+                .map(|output| (BalanceOwner::StakeCredential(output.owner_id), output)),
+        );
+    }
+
+    let mut balances = BTreeMap::<BalanceOwner, AssetAmounts>::new();
+    for (owner, output) in outputs {
+        let era = EraValue::try_from(output.era)
+            .map_err(|_| DbErr::Custom(format!("unknown era {}", output.era)))?;
+        add_output(
+            balances.entry(owner).or_default(),
+            &output_from_payload(&output.payload, era)?,
+            1,
+        );
+    }
+    Ok(balances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_credential_changes, add_output, recomputed_balances, AssetAmounts, BalanceOwner,
+    };
+    use crate::types::AssetPair;
+    use cml_chain::address::Address;
+    use cml_chain::assets::{AssetName, MultiAsset, Value};
+    use cml_chain::transaction::TransactionOutput;
+    use cml_crypto::{RawBytesEncoding, ScriptHash};
+    use cml_multi_era::utils::MultiEraTransactionOutput;
+    use std::collections::BTreeMap;
+
+    const ADDRESS: &str = "addr1wyx22z2s4kasd3w976pnjf9xdty88epjqfvgkmfnscpd0rg3z8y6v";
+    const POLICY_ID: &str = "29d222ce763455e3d7a09a665ce554f00ac89d2e99a1a83d267170c6";
+
+    fn output(coin: u64, tokens: u64) -> MultiEraTransactionOutput {
+        let mut multiasset = MultiAsset::new();
+        if tokens > 0 {
+            multiasset.set(
+                ScriptHash::from_hex(POLICY_ID).unwrap(),
+                AssetName::new(b"MIN".to_vec()).unwrap(),
+                tokens,
+            );
+        }
+        MultiEraTransactionOutput::Shelley(TransactionOutput::new(
+            Address::from_bech32(ADDRESS).unwrap(),
+            Value::new(coin, multiasset),
+            None,
+            None,
+        ))
+    }
+
+    fn token() -> AssetPair {
+        Some((hex::decode(POLICY_ID).unwrap(), b"MIN".to_vec()))
+    }
+
+    #[test]
+    fn spent_outputs_are_subtracted() {
+        let mut amounts = AssetAmounts::new();
+        add_output(&mut amounts, &output(10_000_000, 500), 1);
+        add_output(&mut amounts, &output(4_000_000, 0), 1);
+        add_output(&mut amounts, &output(10_000_000, 500), -1);
+
+        assert_eq!(amounts, BTreeMap::from([(None, 4_000_000), (token(), 0)]));
+    }
+
+    #[test]
+    fn address_changes_are_added_to_their_credential() {
+        let mut changes = BTreeMap::from([
+            (
+                BalanceOwner::Address(1),
+                BTreeMap::from([(None, 10), (token(), 5)]),
+            ),
+            (BalanceOwner::Address(2), BTreeMap::from([(None, -4)])),
+            // no stake credential (ex: enterprise address)
+            (BalanceOwner::Address(3), BTreeMap::from([(None, 7)])),
+        ]);
+        add_credential_changes(&mut changes, [(1, 100), (2, 100)].into_iter());
+
+        assert_eq!(
+            changes[&BalanceOwner::StakeCredential(100)],
+            BTreeMap::from([(None, 6), (token(), 5)])
+        );
+        assert_eq!(changes.len(), 4);
+        assert_eq!(
+            changes[&BalanceOwner::Address(2)],
+            BTreeMap::from([(None, -4)])
+        );
+    }
+
+    #[test]
+    fn recomputed_balance_matches_the_utxo_set() {
+        let asset_ids = BTreeMap::from([(token().unwrap(), 7)]);
+
+        // block 1 creates an output and block 2 spends it
+        let mut after_block_1 = AssetAmounts::new();
+        add_output(&mut after_block_1, &output(10_000_000, 500), 1);
+        let mut after_block_2 = after_block_1.clone();
+        add_output(&mut after_block_2, &output(10_000_000, 500), -1);
+        add_output(&mut after_block_2, &output(4_000_000, 0), 1);
+        assert_ne!(after_block_1, after_block_2);
+
+        // after a rollback of block 2, the balance is recomputed from the UTxO set before it
+        // note: finding the balances to recompute (`last_block_id` is null) needs a database
+        let mut utxo_set = AssetAmounts::new();
+        add_output(&mut utxo_set, &output(10_000_000, 500), 1);
+        assert_eq!(utxo_set, after_block_1);
+        assert_eq!(
+            recomputed_balances(
+                BTreeMap::from([(BalanceOwner::Address(1), utxo_set)]),
+                &asset_ids
+            )
+            .unwrap(),
+            vec![
+                (BalanceOwner::Address(1), None, 10_000_000),
+                (BalanceOwner::Address(1), Some(7), 500),
+            ]
+        );
+
+        // unlike incremental updates, recomputing doesn't keep zero balances
+        assert_eq!(
+            recomputed_balances(
+                BTreeMap::from([(
+                    BalanceOwner::StakeCredential(100),
+                    BTreeMap::from([(None, 2_000_000), (token(), 0)])
+                )]),
+                &asset_ids
+            )
+            .unwrap(),
+            vec![(BalanceOwner::StakeCredential(100), None, 2_000_000)]
+        );
+    }
+}
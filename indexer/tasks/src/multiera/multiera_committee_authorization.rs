@@ -0,0 +1,89 @@
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use cml_multi_era::utils::MultiEraCertificate;
+use entity::{
+    prelude::*,
+    sea_orm::{prelude::*, DatabaseTransaction, Set},
+};
+
+use super::multiera_txs::MultieraTransactionTask;
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::database_task::BlockGlobalInfo;
+use crate::dsl::task_macro::*;
+
+carp_task! {
+  name MultieraCommitteeAuthorizationTask;
+  configuration EmptyConfig;
+  doc "Tracks the hot key authorizations and resignations of constitutional committee members";
+  era multiera;
+  dependencies [MultieraTransactionTask];
+  read [multiera_txs];
+  write [];
+  should_add_task |block, _properties| {
+    block
+      .1
+      .transaction_bodies()
+      .iter()
+      .any(|x| x.certs().is_some())
+  };
+  execute |previous_data, task| handle(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+) -> Result<(), DbErr> {
+    let mut queued_authorizations = vec![];
+
+    for (tx_body, cardano_transaction) in block.1.transaction_bodies().iter().zip(multiera_txs) {
+        let certs = match tx_body.certs() {
+            None => continue,
+            Some(certs) => certs,
+        };
+        for cert in certs {
+            match cert {
+                MultiEraCertificate::AuthCommitteeHotCert(cert) => {
+                    queued_authorizations.push(CommitteeAuthorizationActiveModel {
+                        cold_credential: Set(cert.committee_cold_credential.to_cbor_bytes()),
+                        hot_credential: Set(Some(cert.committee_hot_credential.to_cbor_bytes())),
+                        tx_id: Set(cardano_transaction.id),
+                        anchor_url: Set(None),
+                        anchor_hash: Set(None),
+                        ..Default::default()
+                    });
+                }
+                MultiEraCertificate::ResignCommitteeColdCert(cert) => {
+                    queued_authorizations.push(CommitteeAuthorizationActiveModel {
+                        cold_credential: Set(cert.committee_cold_credential.to_cbor_bytes()),
+                        hot_credential: Set(None),
+                        tx_id: Set(cardano_transaction.id),
+                        anchor_url: Set(cert
+                            .anchor
+                            .as_ref()
+                            .map(|anchor| anchor.anchor_url.get().clone())),
+                        anchor_hash: Set(cert
+                            .anchor
+                            .as_ref()
+                            .map(|anchor| anchor.anchor_doc_hash.to_raw_bytes().to_vec())),
+                        ..Default::default()
+                    });
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    if !queued_authorizations.is_empty() {
+        CommitteeAuthorization::insert_many(queued_authorizations)
+            .exec(db_tx)
+            .await?;
+    }
+
+    Ok(())
+}
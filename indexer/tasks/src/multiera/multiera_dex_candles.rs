@@ -0,0 +1,373 @@
+use std::collections::BTreeMap;
+
+use super::multiera_minswap_v1_swap::MultieraMinSwapV1SwapTask;
+use super::multiera_minswap_v2_swap::MultieraMinSwapV2SwapTask;
+use super::multiera_sundaeswap_v1_swap::MultieraSundaeSwapV1SwapTask;
+use super::multiera_sundaeswap_v3_swap::MultieraSundaeSwapV3SwapTask;
+use super::multiera_txs::MultieraTransactionTask;
+use super::multiera_wingriders_v1_swap::MultieraWingRidersV1SwapTask;
+use super::multiera_wingriders_v2_swap::MultieraWingRidersV2SwapTask;
+use crate::config::DexCandleConfig::DexCandleConfig;
+use crate::dsl::task_macro::*;
+use crate::utils::SlotTimeParams;
+use entity::dex_swap::Operation;
+use entity::sea_orm::{
+    prelude::*, Condition, DatabaseTransaction, FromQueryResult, JoinType, QueryOrder, QuerySelect,
+    Set,
+};
+
+carp_task! {
+  name MultieraDexCandleTask;
+  configuration DexCandleConfig;
+  doc "Aggregates the DEX swaps of the swap tasks that are part of the execution plan into OHLCV candles";
+  era multiera;
+  dependencies [MultieraTransactionTask];
+  optional_dependencies [
+    MultieraWingRidersV1SwapTask,
+    MultieraSundaeSwapV1SwapTask,
+    MultieraMinSwapV1SwapTask,
+    MultieraMinSwapV2SwapTask,
+    MultieraSundaeSwapV3SwapTask,
+    MultieraWingRidersV2SwapTask
+  ];
+  read [multiera_block, multiera_txs];
+  write [];
+  should_add_task |_block, _properties| {
+    // candles may need to be recomputed after a rollback even if the block has no swap
+    true
+  };
+  execute |previous_data, task| handle_candles(
+      task.db_tx,
+      task.block,
+      previous_data.multiera_block.as_ref().unwrap(),
+      &previous_data.multiera_txs,
+      &task.config.intervals,
+  );
+  merge_result |previous_data, _result| {
+  };
+}
+
+/// dex, asset1, asset2, interval, bucket start
+type CandleKey = (i32, Option<i64>, Option<i64>, i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Ohlcv {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume1: u64,
+    volume2: u64,
+}
+
+impl Ohlcv {
+    /// None if the swap has no price (ex: nothing was received)
+    fn from_swap(amount1: u64, amount2: u64) -> Option<Ohlcv> {
+        if amount1 == 0 {
+            return None;
+        }
+        let price = amount2 as f64 / amount1 as f64;
+        Some(Ohlcv {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume1: amount1,
+            volume2: amount2,
+        })
+    }
+
+    fn from_candle(candle: &DexCandleModel) -> Ohlcv {
+        Ohlcv {
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
+            volume1: candle.volume1,
+            volume2: candle.volume2,
+        }
+    }
+
+    /// Adds swaps that happened after the ones of `self`
+    fn merge(&mut self, next: &Ohlcv) {
+        self.high = self.high.max(next.high);
+        self.low = self.low.min(next.low);
+        self.close = next.close;
+        self.volume1 = self.volume1.saturating_add(next.volume1);
+        self.volume2 = self.volume2.saturating_add(next.volume2);
+    }
+
+    fn set(&self, candle: &mut DexCandleActiveModel) {
+        candle.open = Set(self.open);
+        candle.high = Set(self.high);
+        candle.low = Set(self.low);
+        candle.close = Set(self.close);
+        candle.volume1 = Set(self.volume1);
+        candle.volume2 = Set(self.volume2);
+    }
+}
+
+fn bucket_start(time: i64, interval: i64) -> i64 {
+    time - time.rem_euclid(interval)
+}
+
+fn candle_condition(key: &CandleKey) -> Condition {
+    let (dex, asset1, asset2, interval, bucket_start) = *key;
+    Condition::all()
+        .add(DexCandleColumn::Dex.eq(dex))
+        .add(match asset1 {
+            Some(id) => DexCandleColumn::Asset1Id.eq(id),
+            None => DexCandleColumn::Asset1Id.is_null(),
+        })
+        .add(match asset2 {
+            Some(id) => DexCandleColumn::Asset2Id.eq(id),
+            None => DexCandleColumn::Asset2Id.is_null(),
+        })
+        .add(DexCandleColumn::Interval.eq(interval))
+        .add(DexCandleColumn::BucketStart.eq(bucket_start))
+}
+
+fn slot_time_params(block_info: &BlockGlobalInfo) -> Result<SlotTimeParams, DbErr> {
+    block_info.slot_time.ok_or_else(|| {
+        DbErr::Custom("candles need the slot parameters of the network to be known".to_string())
+    })
+}
+
+fn swap_operations() -> Vec<i32> {
+    vec![Operation::Sell.into(), Operation::Buy.into()]
+}
+
+async fn handle_candles(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    database_block: &BlockModel,
+    multiera_txs: &[TransactionModel],
+    intervals: &[u64],
+) -> Result<(), DbErr> {
+    // 1) Recompute the candles that lost swaps in a rollback
+    // note: checked on every block so that it doesn't depend on how the rollback happened
+    recompute_rolled_back_candles(db_tx, block.2, database_block).await?;
+
+    if multiera_txs.is_empty() {
+        return Ok(());
+    }
+
+    // 2) Aggregate the swaps of the block
+    let swaps = DexSwap::find()
+        .filter(DexSwapColumn::TxId.is_in(multiera_txs.iter().map(|tx| tx.id)))
+        .filter(DexSwapColumn::Operation.is_in(swap_operations()))
+        .order_by_asc(DexSwapColumn::TxId)
+        .order_by_asc(DexSwapColumn::Id)
+        .all(db_tx)
+        .await?;
+    if swaps.is_empty() {
+        return Ok(());
+    }
+
+    let time = slot_time_params(block.2)?.slot_to_unix_time(block.1.header().slot()) as i64;
+    let mut block_candles = BTreeMap::<CandleKey, Ohlcv>::new();
+    for swap in swaps.iter() {
+        let ohlcv = match Ohlcv::from_swap(swap.amount1, swap.amount2) {
+            Some(ohlcv) => ohlcv,
+            None => continue,
+        };
+        for &interval in intervals {
+            let interval = interval as i64;
+            let key = (
+                swap.dex,
+                swap.asset1_id,
+                swap.asset2_id,
+                interval,
+                bucket_start(time, interval),
+            );
+            block_candles
+                .entry(key)
+                .and_modify(|candle| candle.merge(&ohlcv))
+                .or_insert(ohlcv);
+        }
+    }
+
+    // 3) Merge them with the candles of the previous blocks
+    let existing_candles = DexCandle::find()
+        .filter(
+            block_candles
+                .keys()
+                .fold(Condition::any(), |condition, key| {
+                    condition.add(candle_condition(key))
+                }),
+        )
+        .all(db_tx)
+        .await?
+        .into_iter()
+        .map(|candle| {
+            (
+                (
+                    candle.dex,
+                    candle.asset1_id,
+                    candle.asset2_id,
+                    candle.interval,
+                    candle.bucket_start,
+                ),
+                candle,
+            )
+        })
+        .collect::<BTreeMap<CandleKey, DexCandleModel>>();
+
+    let mut new_candles = vec![];
+    for (key, ohlcv) in block_candles {
+        match existing_candles.get(&key) {
+            Some(candle) => {
+                let mut merged = Ohlcv::from_candle(candle);
+                merged.merge(&ohlcv);
+                let mut candle: DexCandleActiveModel = candle.clone().into();
+                merged.set(&mut candle);
+                candle.last_block_id = Set(Some(database_block.id));
+                candle.update(db_tx).await?;
+            }
+            None => {
+                let (dex, asset1_id, asset2_id, interval, bucket_start) = key;
+                let mut candle = DexCandleActiveModel {
+                    dex: Set(dex),
+                    asset1_id: Set(asset1_id),
+                    asset2_id: Set(asset2_id),
+                    interval: Set(interval),
+                    bucket_start: Set(bucket_start),
+                    last_block_id: Set(Some(database_block.id)),
+                    ..Default::default()
+                };
+                ohlcv.set(&mut candle);
+                new_candles.push(candle);
+            }
+        }
+    }
+    if !new_candles.is_empty() {
+        DexCandle::insert_many(new_candles).exec(db_tx).await?;
+    }
+
+    Ok(())
+}
+
+/// Candles whose last block got rolled back (see `last_block_id`) are rebuilt from the swaps left
+async fn recompute_rolled_back_candles(
+    db_tx: &DatabaseTransaction,
+    block_info: &BlockGlobalInfo,
+    database_block: &BlockModel,
+) -> Result<(), DbErr> {
+    #[derive(FromQueryResult)]
+    pub struct SwapWithBlock {
+        amount1: u64,
+        amount2: u64,
+        block_id: i32,
+    }
+
+    let candles = DexCandle::find()
+        .filter(DexCandleColumn::LastBlockId.is_null())
+        .all(db_tx)
+        .await?;
+
+    for candle in candles {
+        let slot_time = slot_time_params(block_info)?;
+        let start_slot = slot_time.unix_time_to_slot(candle.bucket_start as u64) as i64;
+        let end_slot =
+            slot_time.unix_time_to_slot((candle.bucket_start + candle.interval) as u64) as i64;
+        let swaps = DexSwap::find()
+            .select_only()
+            .column(DexSwapColumn::Amount1)
+            .column(DexSwapColumn::Amount2)
+            .column(TransactionColumn::BlockId)
+            .join(JoinType::InnerJoin, DexSwapRelation::Transaction.def())
+            .join(JoinType::InnerJoin, TransactionRelation::Block.def())
+            .filter(DexSwapColumn::Dex.eq(candle.dex))
+            .filter(match candle.asset1_id {
+                Some(id) => DexSwapColumn::Asset1Id.eq(id),
+                None => DexSwapColumn::Asset1Id.is_null(),
+            })
+            .filter(match candle.asset2_id {
+                Some(id) => DexSwapColumn::Asset2Id.eq(id),
+                None => DexSwapColumn::Asset2Id.is_null(),
+            })
+            .filter(DexSwapColumn::Operation.is_in(swap_operations()))
+            .filter(BlockColumn::Slot.gte(start_slot))
+            .filter(BlockColumn::Slot.lt(end_slot))
+            // swaps of the current block are added afterwards with the rest of the block
+            .filter(BlockColumn::Id.ne(database_block.id))
+            .order_by_asc(DexSwapColumn::TxId)
+            .order_by_asc(DexSwapColumn::Id)
+            .into_model::<SwapWithBlock>()
+            .all(db_tx)
+            .await?;
+
+        let mut ohlcv: Option<Ohlcv> = None;
+        let mut last_block_id = None;
+        for swap in swaps {
+            if let Some(next) = Ohlcv::from_swap(swap.amount1, swap.amount2) {
+                match &mut ohlcv {
+                    Some(ohlcv) => ohlcv.merge(&next),
+                    None => ohlcv = Some(next),
+                }
+                last_block_id = Some(swap.block_id);
+            }
+        }
+
+        match ohlcv {
+            Some(ohlcv) => {
+                let mut candle: DexCandleActiveModel = candle.into();
+                ohlcv.set(&mut candle);
+                candle.last_block_id = Set(last_block_id);
+                candle.update(db_tx).await?;
+            }
+            None => {
+                candle.delete(db_tx).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bucket_start, Ohlcv};
+    use crate::utils::SlotTimeParams;
+
+    #[test]
+    fn bucket_start_alignment() {
+        assert_eq!(bucket_start(1_700_000_059, 60), 1_700_000_040);
+        assert_eq!(bucket_start(1_700_000_040, 60), 1_700_000_040);
+        assert_eq!(bucket_start(1_700_000_059, 86_400), 1_699_920_000);
+    }
+
+    #[test]
+    fn bucket_slots() {
+        let mainnet = SlotTimeParams::for_network("mainnet").unwrap();
+        let time = mainnet.slot_to_unix_time(100_000_000);
+        assert_eq!(mainnet.unix_time_to_slot(time), 100_000_000);
+
+        // the first slot of a bucket can start after the bucket (ex: Byron slots)
+        let byron_slot_time = mainnet.slot_to_unix_time(10);
+        assert_eq!(mainnet.unix_time_to_slot(byron_slot_time - 19), 10);
+        assert_eq!(mainnet.unix_time_to_slot(byron_slot_time + 1), 11);
+        assert_eq!(mainnet.unix_time_to_slot(0), 0);
+    }
+
+    #[test]
+    fn merge_swaps() {
+        let mut candle = Ohlcv::from_swap(10, 20).unwrap();
+        candle.merge(&Ohlcv::from_swap(10, 50).unwrap());
+        candle.merge(&Ohlcv::from_swap(20, 10).unwrap());
+        candle.merge(&Ohlcv::from_swap(10, 30).unwrap());
+
+        assert_eq!(
+            candle,
+            Ohlcv {
+                open: 2.0,
+                high: 5.0,
+                low: 0.5,
+                close: 3.0,
+                volume1: 50,
+                volume2: 110,
+            }
+        );
+        assert_eq!(Ohlcv::from_swap(0, 10), None);
+    }
+}
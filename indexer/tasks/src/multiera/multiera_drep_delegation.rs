@@ -1,4 +1,5 @@
 use crate::{
+    multiera::multiera_drep_registration::MultieraDrepRegistrationTask,
     multiera::multiera_stake_credentials::MultieraStakeCredentialTask,
     types::{AddressCredentialRelationValue, TxCredentialRelationValue},
 };
@@ -6,6 +7,7 @@ use cml_core::serialization::Serialize;
 use cml_crypto::RawBytesEncoding;
 use cml_multi_era::utils::MultiEraCertificate;
 use entity::{
+    drep_registration::DrepEvent,
     prelude::*,
     sea_orm::{prelude::*, DatabaseTransaction},
 };
@@ -27,7 +29,9 @@ carp_task! {
   configuration EmptyConfig;
   doc "Tracks stake delegation actions to dreps";
   era multiera;
+  version 2;
   dependencies [MultieraStakeCredentialTask];
+  optional_dependencies [MultieraDrepRegistrationTask];
   read [multiera_txs, multiera_stake_credential];
   write [];
   should_add_task |block, _properties| {
@@ -102,13 +106,12 @@ async fn handle(
                     .one(db_tx)
                     .await?;
 
-                if let Some((previous, drep)) = previous_entry
-                    .as_ref()
-                    .and_then(|entry| entry.drep_credential.as_ref())
-                    .zip(drep.as_ref())
-                {
-                    // re-delegating shouldn't have any effect.
-                    if previous == drep {
+                if let (Some(previous), Some(drep)) = (previous_entry.as_ref(), drep.as_ref()) {
+                    // re-delegating shouldn't have any effect,
+                    // unless the drep retired in between (which removed the delegation)
+                    if previous.drep_credential.as_ref() == Some(drep)
+                        && !drep_retired_since(db_tx, drep, previous.tx_id).await?
+                    {
                         continue;
                     }
                 }
@@ -130,3 +133,19 @@ async fn handle(
 
     Ok(())
 }
+
+/// Whether the drep retired after the transaction `tx_id`
+/// note: this is only known if `MultieraDrepRegistrationTask` is part of the execution plan
+async fn drep_retired_since(
+    db_tx: &DatabaseTransaction,
+    drep: &[u8],
+    tx_id: i64,
+) -> Result<bool, DbErr> {
+    let retirement = DrepRegistration::find()
+        .filter(DrepRegistrationColumn::DrepCredential.eq(drep.to_vec()))
+        .filter(DrepRegistrationColumn::TxId.gt(tx_id))
+        .filter(DrepRegistrationColumn::Event.eq(i32::from(DrepEvent::Retirement)))
+        .one(db_tx)
+        .await?;
+    Ok(retirement.is_some())
+}
@@ -0,0 +1,97 @@
+use cml_chain::governance::Anchor;
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use cml_multi_era::utils::MultiEraCertificate;
+use entity::{
+    drep_registration::DrepEvent,
+    prelude::*,
+    sea_orm::{prelude::*, DatabaseTransaction, Set},
+};
+
+use super::multiera_txs::MultieraTransactionTask;
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::database_task::BlockGlobalInfo;
+use crate::dsl::task_macro::*;
+
+carp_task! {
+  name MultieraDrepRegistrationTask;
+  configuration EmptyConfig;
+  doc "Keeps the history of the registrations, updates and retirements of dreps";
+  era multiera;
+  dependencies [MultieraTransactionTask];
+  read [multiera_txs];
+  write [];
+  should_add_task |block, _properties| {
+    block
+      .1
+      .transaction_bodies()
+      .iter()
+      .any(|x| x.certs().is_some())
+  };
+  execute |previous_data, task| handle(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+) -> Result<(), DbErr> {
+    let mut queued_events = vec![];
+
+    for (tx_body, cardano_transaction) in block.1.transaction_bodies().iter().zip(multiera_txs) {
+        let certs = match tx_body.certs() {
+            None => continue,
+            Some(certs) => certs,
+        };
+        for cert in certs {
+            let (credential, event, deposit, anchor) = match &cert {
+                MultiEraCertificate::RegDrepCert(cert) => (
+                    &cert.drep_credential,
+                    DrepEvent::Registration,
+                    Some(cert.deposit),
+                    cert.anchor.as_ref(),
+                ),
+                MultiEraCertificate::UpdateDrepCert(cert) => (
+                    &cert.drep_credential,
+                    DrepEvent::Update,
+                    None,
+                    cert.anchor.as_ref(),
+                ),
+                MultiEraCertificate::UnregDrepCert(cert) => (
+                    &cert.drep_credential,
+                    DrepEvent::Retirement,
+                    Some(cert.deposit),
+                    None,
+                ),
+                _ => continue,
+            };
+
+            queued_events.push(DrepRegistrationActiveModel {
+                drep_credential: Set(credential.to_cbor_bytes()),
+                tx_id: Set(cardano_transaction.id),
+                event: Set(event.into()),
+                deposit: Set(deposit),
+                anchor_url: Set(anchor.map(|anchor| anchor.anchor_url.get().clone())),
+                anchor_hash: Set(anchor.map(anchor_hash)),
+                ..Default::default()
+            });
+        }
+    }
+
+    if !queued_events.is_empty() {
+        DrepRegistration::insert_many(queued_events)
+            .exec(db_tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn anchor_hash(anchor: &Anchor) -> Vec<u8> {
+    anchor.anchor_doc_hash.to_raw_bytes().to_vec()
+}
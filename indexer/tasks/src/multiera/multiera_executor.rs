@@ -24,6 +24,8 @@ pub async fn process_multiera_block(
 
     let mut dispatcher_builder = DispatcherBuilder::new();
 
+    // note: tasks are sorted by dependencies when the plan is loaded
+    // so this iterates over them in an order the dispatcher accepts
     for (task_name, val) in exec_plan.0.iter() {
         if let toml::value::Value::Table(_task_props) = val {
             let entry = find_task_registry_entry(task_name);
@@ -0,0 +1,81 @@
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::{dsl::task_macro::*, multiera::multiera_txs::MultieraTransactionTask};
+use cml_chain::governance::{GovAction, GovActionId, ProposalProcedure};
+use cml_crypto::{RawBytesEncoding, Serialize};
+use entity::governance_proposals::{ActiveModel, GovActionType};
+use sea_orm::{prelude::*, Set};
+
+carp_task! {
+  name MultieraGovernanceProposalTask;
+  configuration EmptyConfig;
+  doc "Tracks the governance actions proposed in transactions, along with the gov action id (transaction hash and index) votes use to reference them";
+  era multiera;
+  dependencies [MultieraTransactionTask];
+  read [multiera_txs];
+  write [];
+  should_add_task |block, _properties| {
+    block.1.transaction_bodies().iter().any(|x| x.proposal_procedures().is_some())
+  };
+  execute |previous_data, task| handle(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+) -> Result<(), DbErr> {
+    let mut queued_inserts = vec![];
+    for (tx_body, cardano_transaction) in block.1.transaction_bodies().iter().zip(multiera_txs) {
+        let proposal_procedures: &[ProposalProcedure] =
+            if let Some(proposal_procedures) = tx_body.proposal_procedures() {
+                proposal_procedures.as_ref()
+            } else {
+                continue;
+            };
+
+        let tx_hash = tx_body.hash();
+        for (index, proposal) in proposal_procedures.iter().enumerate() {
+            // votes reference the proposal by the hash of its transaction and its index in it
+            let gov_action_id = GovActionId::new(tx_hash, index as u64);
+
+            queued_inserts.push(ActiveModel {
+                tx_id: Set(cardano_transaction.id),
+                index: Set(index as i64),
+                gov_action_id: Set(gov_action_id.to_cbor_bytes()),
+                action_tx_hash: Set(tx_hash.to_raw_bytes().to_vec()),
+                action_type: Set(gov_action_type(&proposal.gov_action).into()),
+                deposit: Set(proposal.deposit),
+                reward_address: Set(proposal.reward_account.clone().to_address().to_raw_bytes()),
+                anchor_url: Set(proposal.anchor.anchor_url.get().clone()),
+                anchor_hash: Set(proposal.anchor.anchor_doc_hash.to_raw_bytes().to_vec()),
+                gov_action: Set(proposal.gov_action.to_cbor_bytes()),
+                ..Default::default()
+            })
+        }
+    }
+
+    if !queued_inserts.is_empty() {
+        GovernanceProposal::insert_many(queued_inserts.into_iter())
+            .exec(db_tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn gov_action_type(gov_action: &GovAction) -> GovActionType {
+    match gov_action {
+        GovAction::ParameterChangeAction(_) => GovActionType::ParameterChange,
+        GovAction::HardForkInitiationAction(_) => GovActionType::HardForkInitiation,
+        GovAction::TreasuryWithdrawalsAction(_) => GovActionType::TreasuryWithdrawals,
+        GovAction::NoConfidence(_) => GovActionType::NoConfidence,
+        GovAction::UpdateCommittee(_) => GovActionType::UpdateCommittee,
+        GovAction::NewConstitution(_) => GovActionType::NewConstitution,
+        GovAction::InfoAction { .. } => GovActionType::Info,
+    }
+}
@@ -1,11 +1,11 @@
 use super::dex::common::{handle_swap, DexType};
 use super::multiera_used_inputs::MultieraUsedInputTask;
-use crate::config::EmptyConfig::EmptyConfig;
+use crate::config::DexConfig::DexConfig;
 use crate::dsl::task_macro::*;
 
 carp_task! {
   name MultieraMinSwapV1SwapTask;
-  configuration EmptyConfig;
+  configuration DexConfig;
   doc "Adds Minswap V1 swaps to the database";
   era multiera;
   dependencies [MultieraUsedInputTask];
@@ -21,6 +21,7 @@ carp_task! {
       &previous_data.multiera_addresses,
       &previous_data.multiera_used_inputs_to_outputs_map,
       DexType::MinSwapV1,
+      &task.config,
   );
   merge_result |previous_data, _result| {
   };
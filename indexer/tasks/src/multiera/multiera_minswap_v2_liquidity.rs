@@ -0,0 +1,29 @@
+use super::dex::common::{handle_liquidity, DexType};
+use super::multiera_asset_mint::MultieraAssetMintTask;
+use super::multiera_used_inputs::MultieraUsedInputTask;
+use crate::config::DexConfig::DexConfig;
+use crate::dsl::task_macro::*;
+
+carp_task! {
+  name MultieraMinSwapV2LiquidityTask;
+  configuration DexConfig;
+  doc "Adds Minswap V2 liquidity deposits and withdrawals to the database";
+  era multiera;
+  dependencies [MultieraUsedInputTask, MultieraAssetMintTask];
+  read [multiera_txs, multiera_addresses, multiera_used_inputs_to_outputs_map];
+  write [];
+  should_add_task |block, _properties| {
+    block.1.transaction_bodies().iter().any(|tx| !tx.outputs().is_empty())
+  };
+  execute |previous_data, task| handle_liquidity(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+      &previous_data.multiera_addresses,
+      &previous_data.multiera_used_inputs_to_outputs_map,
+      DexType::MinSwapV2,
+      &task.config,
+  );
+  merge_result |previous_data, _result| {
+  };
+}
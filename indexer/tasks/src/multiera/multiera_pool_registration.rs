@@ -0,0 +1,124 @@
+use cbor_event::{se::Serializer, Len};
+use cml_chain::certs::{PoolParams, Relay};
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use cml_multi_era::utils::MultiEraCertificate;
+use entity::{
+    prelude::*,
+    sea_orm::{prelude::*, DatabaseTransaction, Set},
+};
+
+use super::multiera_txs::MultieraTransactionTask;
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::database_task::BlockGlobalInfo;
+use crate::dsl::task_macro::*;
+
+carp_task! {
+  name MultieraPoolRegistrationTask;
+  configuration EmptyConfig;
+  doc "Keeps the history of the registrations, updates and retirements of stake pools";
+  era multiera;
+  dependencies [MultieraTransactionTask];
+  read [multiera_txs];
+  write [];
+  should_add_task |block, _properties| {
+    block
+      .1
+      .transaction_bodies()
+      .iter()
+      .any(|x| x.certs().is_some())
+  };
+  execute |previous_data, task| handle(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+) -> Result<(), DbErr> {
+    let mut queued_registrations = vec![];
+
+    for (tx_body, cardano_transaction) in block.1.transaction_bodies().iter().zip(multiera_txs) {
+        let certs = match tx_body.certs() {
+            None => continue,
+            Some(certs) => certs,
+        };
+        for cert in certs {
+            match cert {
+                MultiEraCertificate::PoolRegistration(registration) => {
+                    queued_registrations.push(pool_registration_model(
+                        &registration.pool_params,
+                        cardano_transaction.id,
+                    ));
+                }
+                MultiEraCertificate::PoolRetirement(retirement) => {
+                    queued_registrations.push(PoolRegistrationActiveModel {
+                        pool_credential: Set(retirement.pool.to_raw_bytes().to_vec()),
+                        tx_id: Set(cardano_transaction.id),
+                        retiring_epoch: Set(Some(retirement.epoch as i64)),
+                        ..Default::default()
+                    });
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    if !queued_registrations.is_empty() {
+        PoolRegistration::insert_many(queued_registrations)
+            .exec(db_tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Registrations and updates use the same certificate
+/// so the latest registration of a pool holds its current parameters
+pub(crate) fn pool_registration_model(
+    params: &PoolParams,
+    tx_id: i64,
+) -> PoolRegistrationActiveModel {
+    PoolRegistrationActiveModel {
+        pool_credential: Set(params.operator.to_raw_bytes().to_vec()),
+        tx_id: Set(tx_id),
+        vrf_key_hash: Set(Some(params.vrf_keyhash.to_raw_bytes().to_vec())),
+        pledge: Set(Some(params.pledge)),
+        cost: Set(Some(params.cost)),
+        margin_numerator: Set(Some(params.margin.start)),
+        margin_denominator: Set(Some(params.margin.end)),
+        reward_address: Set(Some(
+            params.reward_account.clone().to_address().to_raw_bytes(),
+        )),
+        metadata_url: Set(params
+            .pool_metadata
+            .as_ref()
+            .map(|metadata| metadata.url.get().clone())),
+        metadata_hash: Set(params
+            .pool_metadata
+            .as_ref()
+            .map(|metadata| metadata.pool_metadata_hash.to_raw_bytes().to_vec())),
+        owners: Set(Some(params.pool_owners.to_cbor_bytes())),
+        relays: Set(Some(relays_cbor(&params.relays))),
+        pool_params: Set(Some(params.to_cbor_bytes())),
+        retiring_epoch: Set(None),
+        ..Default::default()
+    }
+}
+
+fn relays_cbor(relays: &[Relay]) -> Vec<u8> {
+    let mut serializer = Serializer::new_vec();
+    // note: writing to a vec can't fail
+    serializer
+        .write_array(Len::Len(relays.len() as u64))
+        .unwrap();
+    for relay in relays {
+        serializer.write_raw_bytes(&relay.to_cbor_bytes()).unwrap();
+    }
+    serializer.finalize()
+}
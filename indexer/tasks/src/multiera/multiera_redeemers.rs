@@ -0,0 +1,316 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use cml_chain::certs::Credential;
+use cml_chain::governance::{GovActionId, Voter};
+use cml_chain::plutus::{ExUnits, PlutusData, RedeemerTag, Redeemers};
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use cml_multi_era::utils::MultiEraCertificate;
+use cml_multi_era::MultiEraTransactionBody;
+use entity::{
+    prelude::*,
+    redeemer::Purpose,
+    sea_orm::{prelude::*, DatabaseTransaction, Set},
+};
+
+use super::multiera_used_inputs::MultieraUsedInputTask;
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::database_task::BlockGlobalInfo;
+use crate::dsl::task_macro::*;
+use crate::era_common::OutputWithTxData;
+
+carp_task! {
+  name MultieraRedeemerTask;
+  configuration EmptyConfig;
+  doc "Stores the redeemers of every transaction with their purpose and execution units";
+  era multiera;
+  dependencies [MultieraUsedInputTask];
+  read [multiera_txs, multiera_used_inputs_to_outputs_map];
+  write [];
+  should_add_task |block, _properties| {
+    block
+      .1
+      .transaction_witness_sets()
+      .iter()
+      .any(|witness_set| witness_set.redeemers.is_some())
+  };
+  execute |previous_data, task| handle(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+      &previous_data.multiera_used_inputs_to_outputs_map,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Result<(), DbErr> {
+    let mut queued_redeemers = vec![];
+
+    for ((tx_body, tx_witness_set), cardano_transaction) in block
+        .1
+        .transaction_bodies()
+        .iter()
+        .zip(block.1.transaction_witness_sets().iter())
+        .zip(multiera_txs)
+    {
+        let redeemers = match &tx_witness_set.redeemers {
+            None => continue,
+            Some(redeemers) => redeemers,
+        };
+
+        for (tag, index, data, ex_units) in get_redeemers(redeemers) {
+            let (purpose, utxo_id, target) = match tag {
+                RedeemerTag::Spend => (
+                    Purpose::Spend,
+                    spent_output_id(tx_body, index, multiera_used_inputs_to_outputs_map),
+                    None,
+                ),
+                RedeemerTag::Mint => (Purpose::Mint, None, minted_policy(tx_body, index)),
+                RedeemerTag::Cert => (Purpose::Cert, None, cert_credential(tx_body, index)),
+                RedeemerTag::Reward => {
+                    (Purpose::Reward, None, withdrawal_credential(tx_body, index))
+                }
+                RedeemerTag::Voting => (Purpose::Vote, None, voter(tx_body, index)),
+                RedeemerTag::Proposing => (
+                    Purpose::Propose,
+                    None,
+                    Some(GovActionId::new(tx_body.hash(), index).to_cbor_bytes()),
+                ),
+            };
+
+            queued_redeemers.push(RedeemerActiveModel {
+                tx_id: Set(cardano_transaction.id),
+                tag: Set(purpose.into()),
+                index: Set(index as i64),
+                utxo_id: Set(utxo_id),
+                target: Set(target),
+                data: Set(data.to_cbor_bytes()),
+                ex_units_mem: Set(ex_units.mem),
+                ex_units_steps: Set(ex_units.steps),
+                ..Default::default()
+            });
+        }
+    }
+
+    if !queued_redeemers.is_empty() {
+        Redeemer::insert_many(queued_redeemers).exec(db_tx).await?;
+    }
+
+    Ok(())
+}
+
+fn get_redeemers(redeemers: &Redeemers) -> Vec<(RedeemerTag, u64, &PlutusData, &ExUnits)> {
+    match redeemers {
+        Redeemers::ArrLegacyRedeemer {
+            arr_legacy_redeemer,
+            arr_legacy_redeemer_encoding: _,
+        } => arr_legacy_redeemer
+            .iter()
+            .map(|redeemer| {
+                (
+                    redeemer.tag,
+                    redeemer.index,
+                    &redeemer.data,
+                    &redeemer.ex_units,
+                )
+            })
+            .collect(),
+        Redeemers::MapRedeemerKeyToRedeemerVal {
+            map_redeemer_key_to_redeemer_val,
+            map_redeemer_key_to_redeemer_val_encoding: _,
+        } => map_redeemer_key_to_redeemer_val
+            .iter()
+            .map(|(key, val)| (key.tag, key.index, &val.data, &val.ex_units))
+            .collect(),
+    }
+}
+
+/// Spend redeemers index the inputs sorted by (tx hash, index)
+/// Only the inputs of valid transactions are in the map (failed ones consume their collateral)
+fn spent_output_id(
+    tx_body: &MultiEraTransactionBody,
+    index: u64,
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Option<i64> {
+    let mut inputs = tx_body.inputs();
+    inputs.sort_by(|left, right| match left.hash().cmp(&right.hash()) {
+        Ordering::Equal => left.index().cmp(&right.index()),
+        ordering => ordering,
+    });
+    let input = inputs.get(index as usize)?;
+    multiera_used_inputs_to_outputs_map
+        .get(&input.hash()?.to_raw_bytes().to_vec())?
+        .get(&(input.index()? as i64))
+        .map(|output| output.model.id)
+}
+
+/// Mint redeemers index the policy ids in byte order
+fn minted_policy(tx_body: &MultiEraTransactionBody, index: u64) -> Option<Vec<u8>> {
+    let mut policies = tx_body
+        .mint()?
+        .iter()
+        .map(|(policy_id, _assets)| policy_id.to_raw_bytes().to_vec())
+        .collect::<Vec<_>>();
+    policies.sort();
+    policies.into_iter().nth(index as usize)
+}
+
+/// Script credentials come before key credentials in the ledger ordering
+fn credential_order(credential: &Credential) -> (u8, Vec<u8>) {
+    match credential {
+        Credential::Script { hash, .. } => (0, hash.to_raw_bytes().to_vec()),
+        Credential::PubKey { hash, .. } => (1, hash.to_raw_bytes().to_vec()),
+    }
+}
+
+/// Reward redeemers index the withdrawals sorted by credential
+fn withdrawal_credential(tx_body: &MultiEraTransactionBody, index: u64) -> Option<Vec<u8>> {
+    let mut credentials = tx_body
+        .withdrawals()?
+        .keys()
+        .map(|reward_address| reward_address.payment.clone())
+        .collect::<Vec<_>>();
+    credentials.sort_by_key(credential_order);
+    credentials
+        .get(index as usize)
+        .map(|credential| credential.to_cbor_bytes())
+}
+
+/// Cert redeemers index the certificates in the order of the transaction
+fn cert_credential(tx_body: &MultiEraTransactionBody, index: u64) -> Option<Vec<u8>> {
+    let cert = tx_body.certs()?.into_iter().nth(index as usize)?;
+    certificate_credential(&cert).map(|credential| credential.to_cbor_bytes())
+}
+
+/// Credential whose script has to validate the certificate (pools and genesis keys can't be scripts)
+fn certificate_credential(cert: &MultiEraCertificate) -> Option<Credential> {
+    let credential = match cert {
+        MultiEraCertificate::StakeRegistration(cert) => &cert.stake_credential,
+        MultiEraCertificate::StakeDeregistration(cert) => &cert.stake_credential,
+        MultiEraCertificate::StakeDelegation(cert) => &cert.stake_credential,
+        MultiEraCertificate::RegCert(cert) => &cert.stake_credential,
+        MultiEraCertificate::UnregCert(cert) => &cert.stake_credential,
+        MultiEraCertificate::VoteDelegCert(cert) => &cert.stake_credential,
+        MultiEraCertificate::StakeVoteDelegCert(cert) => &cert.stake_credential,
+        MultiEraCertificate::StakeRegDelegCert(cert) => &cert.stake_credential,
+        MultiEraCertificate::VoteRegDelegCert(cert) => &cert.stake_credential,
+        MultiEraCertificate::StakeVoteRegDelegCert(cert) => &cert.stake_credential,
+        MultiEraCertificate::AuthCommitteeHotCert(cert) => &cert.committee_cold_credential,
+        MultiEraCertificate::ResignCommitteeColdCert(cert) => &cert.committee_cold_credential,
+        MultiEraCertificate::RegDrepCert(cert) => &cert.drep_credential,
+        MultiEraCertificate::UnregDrepCert(cert) => &cert.drep_credential,
+        MultiEraCertificate::UpdateDrepCert(cert) => &cert.drep_credential,
+        MultiEraCertificate::PoolRegistration(_)
+        | MultiEraCertificate::PoolRetirement(_)
+        | MultiEraCertificate::GenesisKeyDelegation(_)
+        | MultiEraCertificate::MoveInstantaneousRewardsCert(_) => return None,
+    };
+    Some(credential.clone())
+}
+
+/// Voters are sorted by role (committee, drep, pool) then credential, like in the ledger
+fn voter_order(voter: &Voter) -> (u8, (u8, Vec<u8>)) {
+    match voter {
+        Voter::ConstitutionalCommitteeHotKeyHash {
+            ed25519_key_hash, ..
+        } => (
+            0,
+            credential_order(&Credential::new_pub_key(*ed25519_key_hash)),
+        ),
+        Voter::ConstitutionalCommitteeHotScriptHash { script_hash, .. } => {
+            (0, credential_order(&Credential::new_script(*script_hash)))
+        }
+        Voter::DRepKeyHash {
+            ed25519_key_hash, ..
+        } => (
+            1,
+            credential_order(&Credential::new_pub_key(*ed25519_key_hash)),
+        ),
+        Voter::DRepScriptHash { script_hash, .. } => {
+            (1, credential_order(&Credential::new_script(*script_hash)))
+        }
+        Voter::StakingPoolKeyHash {
+            ed25519_key_hash, ..
+        } => (
+            2,
+            credential_order(&Credential::new_pub_key(*ed25519_key_hash)),
+        ),
+    }
+}
+
+/// Vote redeemers index the voters in the ledger order (see `voter_order`)
+fn voter(tx_body: &MultiEraTransactionBody, index: u64) -> Option<Vec<u8>> {
+    let mut voters = tx_body
+        .voting_procedures()?
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    voters.sort_by_key(voter_order);
+    voters
+        .get(index as usize)
+        .map(|voter| voter.to_cbor_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{certificate_credential, credential_order, voter_order};
+    use cml_chain::certs::{Credential, PoolRetirement, StakeDelegation};
+    use cml_chain::governance::Voter;
+    use cml_crypto::{Ed25519KeyHash, RawBytesEncoding, ScriptHash};
+    use cml_multi_era::utils::MultiEraCertificate;
+
+    #[test]
+    fn script_credentials_first() {
+        let key = Credential::new_pub_key(Ed25519KeyHash::from_raw_bytes(&[0; 28]).unwrap());
+        let script = Credential::new_script(ScriptHash::from_raw_bytes(&[1; 28]).unwrap());
+
+        let mut credentials = vec![key.clone(), script.clone()];
+        credentials.sort_by_key(credential_order);
+        assert_eq!(credentials, vec![script, key]);
+    }
+
+    #[test]
+    fn voters_by_role_then_credential() {
+        let key_hash = Ed25519KeyHash::from_raw_bytes(&[0; 28]).unwrap();
+        let script_hash = ScriptHash::from_raw_bytes(&[1; 28]).unwrap();
+        let pool = Voter::new_staking_pool_key_hash(key_hash);
+        let drep_key = Voter::new_d_rep_key_hash(key_hash);
+        let drep_script = Voter::new_d_rep_script_hash(script_hash);
+        let committee_key = Voter::new_constitutional_committee_hot_key_hash(key_hash);
+
+        let mut voters = vec![
+            pool.clone(),
+            drep_key.clone(),
+            drep_script.clone(),
+            committee_key.clone(),
+        ];
+        voters.sort_by_key(voter_order);
+        assert_eq!(voters, vec![committee_key, drep_script, drep_key, pool]);
+    }
+
+    #[test]
+    fn cert_credentials() {
+        let key_hash = Ed25519KeyHash::from_raw_bytes(&[0; 28]).unwrap();
+        let script = Credential::new_script(ScriptHash::from_raw_bytes(&[1; 28]).unwrap());
+
+        assert_eq!(
+            certificate_credential(&MultiEraCertificate::StakeDelegation(StakeDelegation::new(
+                script.clone(),
+                key_hash
+            ))),
+            Some(script)
+        );
+        assert_eq!(
+            certificate_credential(&MultiEraCertificate::PoolRetirement(PoolRetirement::new(
+                key_hash, 300
+            ))),
+            None
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use cml_chain::transaction::TransactionWitnessSet;
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use cml_multi_era::utils::MultiEraTransactionOutput;
+use cml_multi_era::MultiEraTransactionBody;
+use entity::{
+    prelude::*,
+    script::ScriptType,
+    sea_orm::{prelude::*, DatabaseTransaction, Set},
+};
+
+use super::multiera_used_outputs::MultieraOutputTask;
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::database_task::BlockGlobalInfo;
+use crate::dsl::task_macro::*;
+
+carp_task! {
+  name MultieraScriptTask;
+  configuration EmptyConfig;
+  doc "Adds the native and Plutus scripts from witnesses and reference scripts, and links reference script outputs to them";
+  era multiera;
+  dependencies [MultieraOutputTask];
+  read [multiera_txs, multiera_outputs];
+  write [];
+  should_add_task |block, _properties| {
+    block.1.transaction_witness_sets().iter().any(|witness_set| {
+      !witness_scripts(witness_set).is_empty()
+    }) || block.1.transaction_bodies().iter().any(|tx| {
+      created_outputs(tx).iter().any(|(_, output)| reference_script(output).is_some())
+    })
+  };
+  execute |previous_data, task| handle_scripts(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+      &previous_data.multiera_outputs,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle_scripts(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+    multiera_outputs: &[TransactionOutputModel],
+) -> Result<(), DbErr> {
+    let output_ids = multiera_outputs
+        .iter()
+        .map(|output| ((output.tx_id, output.output_index as usize), output.id))
+        .collect::<BTreeMap<_, _>>();
+
+    // script hash -> (script, first tx)
+    let mut hash_to_script = BTreeMap::<Vec<u8>, (cml_chain::Script, i64)>::new();
+    // output id -> script hash
+    let mut reference_scripts = BTreeMap::<i64, Vec<u8>>::new();
+
+    for ((tx_body, tx_witness_set), cardano_transaction) in block
+        .1
+        .transaction_bodies()
+        .iter()
+        .zip(block.1.transaction_witness_sets().iter())
+        .zip(multiera_txs)
+    {
+        for script in witness_scripts(tx_witness_set) {
+            hash_to_script
+                .entry(script.hash().to_raw_bytes().to_vec())
+                .or_insert_with(|| (script, cardano_transaction.id));
+        }
+
+        for (output_id, script) in reference_scripts(
+            cardano_transaction.id,
+            created_outputs(tx_body),
+            &output_ids,
+        ) {
+            let hash = script.hash().to_raw_bytes().to_vec();
+            reference_scripts.insert(output_id, hash.clone());
+            hash_to_script
+                .entry(hash)
+                .or_insert((script, cardano_transaction.id));
+        }
+    }
+
+    if hash_to_script.is_empty() {
+        return Ok(());
+    }
+
+    // 1) Get the scripts that were already in the DB
+    let mut hash_to_id = Script::find()
+        .filter(ScriptColumn::Hash.is_in(hash_to_script.keys().cloned()))
+        .all(db_tx)
+        .await?
+        .into_iter()
+        .map(|script| (script.hash, script.id))
+        .collect::<BTreeMap<_, _>>();
+
+    // 2) Add the scripts that were not already in the DB
+    let to_add = hash_to_script
+        .iter()
+        .filter(|(hash, _)| !hash_to_id.contains_key(*hash))
+        .map(|(hash, (script, first_tx))| {
+            let (script_type, cbor) = script_type_and_cbor(script);
+            ScriptActiveModel {
+                hash: Set(hash.clone()),
+                script_type: Set(script_type.into()),
+                size: Set(cbor.len() as i32),
+                cbor: Set(cbor),
+                first_tx: Set(*first_tx),
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+    if !to_add.is_empty() {
+        let new_entries = Script::insert_many(to_add)
+            .exec_many_with_returning(db_tx)
+            .await?;
+        hash_to_id.extend(new_entries.into_iter().map(|entry| (entry.hash, entry.id)));
+    }
+
+    // 3) Link the outputs to their reference script
+    if !reference_scripts.is_empty() {
+        ReferenceScript::insert_many(reference_scripts.into_iter().map(|(output_id, hash)| {
+            ReferenceScriptActiveModel {
+                output_id: Set(output_id),
+                script_id: Set(*hash_to_id.get(&hash).unwrap()),
+                ..Default::default()
+            }
+        }))
+        .exec(db_tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Outputs the transaction can create, with their index
+/// (the collateral return is at index outputs.len(), like in `MultieraOutputTask`)
+fn created_outputs(tx_body: &MultiEraTransactionBody) -> Vec<(usize, MultiEraTransactionOutput)> {
+    let outputs = tx_body.outputs();
+    let collateral_return = tx_body
+        .collateral_return()
+        .map(|output| (outputs.len(), output));
+    outputs
+        .into_iter()
+        .enumerate()
+        .chain(collateral_return)
+        .collect()
+}
+
+fn reference_script(output: &MultiEraTransactionOutput) -> Option<&cml_chain::Script> {
+    match output {
+        MultiEraTransactionOutput::Shelley(output) => output.script_ref(),
+        MultiEraTransactionOutput::Byron(_) => None,
+    }
+}
+
+/// Output id and reference script of the outputs that were created
+/// note: multiera_outputs only has the regular outputs of valid txs and the collateral return of failed ones
+fn reference_scripts(
+    tx_id: i64,
+    created: Vec<(usize, MultiEraTransactionOutput)>,
+    output_ids: &BTreeMap<(i64, usize), i64>,
+) -> Vec<(i64, cml_chain::Script)> {
+    created
+        .iter()
+        .filter_map(|(idx, output)| {
+            let script = reference_script(output)?;
+            let output_id = output_ids.get(&(tx_id, *idx))?;
+            Some((*output_id, script.clone()))
+        })
+        .collect()
+}
+
+fn witness_scripts(witness_set: &TransactionWitnessSet) -> Vec<cml_chain::Script> {
+    let mut scripts = vec![];
+    if let Some(native_scripts) = &witness_set.native_scripts {
+        scripts.extend(
+            native_scripts
+                .iter()
+                .cloned()
+                .map(cml_chain::Script::new_native),
+        );
+    }
+    if let Some(plutus_scripts) = &witness_set.plutus_v1_scripts {
+        scripts.extend(
+            plutus_scripts
+                .iter()
+                .cloned()
+                .map(cml_chain::Script::new_plutus_v1),
+        );
+    }
+    if let Some(plutus_scripts) = &witness_set.plutus_v2_scripts {
+        scripts.extend(
+            plutus_scripts
+                .iter()
+                .cloned()
+                .map(cml_chain::Script::new_plutus_v2),
+        );
+    }
+    if let Some(plutus_scripts) = &witness_set.plutus_v3_scripts {
+        scripts.extend(
+            plutus_scripts
+                .iter()
+                .cloned()
+                .map(cml_chain::Script::new_plutus_v3),
+        );
+    }
+    scripts
+}
+
+fn script_type_and_cbor(script: &cml_chain::Script) -> (ScriptType, Vec<u8>) {
+    match script {
+        cml_chain::Script::Native { script, .. } => (ScriptType::Native, script.to_cbor_bytes()),
+        cml_chain::Script::PlutusV1 { script, .. } => {
+            (ScriptType::PlutusV1, script.to_cbor_bytes())
+        }
+        cml_chain::Script::PlutusV2 { script, .. } => {
+            (ScriptType::PlutusV2, script.to_cbor_bytes())
+        }
+        cml_chain::Script::PlutusV3 { script, .. } => {
+            (ScriptType::PlutusV3, script.to_cbor_bytes())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reference_scripts, script_type_and_cbor};
+    use cml_chain::address::Address;
+    use cml_chain::assets::{MultiAsset, Value};
+    use cml_chain::transaction::{NativeScript, TransactionOutput};
+    use cml_chain::Script;
+    use cml_core::serialization::Serialize;
+    use cml_crypto::{Ed25519KeyHash, RawBytesEncoding};
+    use cml_multi_era::utils::MultiEraTransactionOutput;
+    use entity::script::ScriptType;
+    use std::collections::BTreeMap;
+
+    const ADDRESS: &str = "addr1wyx22z2s4kasd3w976pnjf9xdty88epjqfvgkmfnscpd0rg3z8y6v";
+
+    fn native_script(key: u8) -> NativeScript {
+        NativeScript::new_script_pubkey(Ed25519KeyHash::from_raw_bytes(&[key; 28]).unwrap())
+    }
+
+    fn output(script: Option<Script>) -> MultiEraTransactionOutput {
+        MultiEraTransactionOutput::Shelley(TransactionOutput::new(
+            Address::from_bech32(ADDRESS).unwrap(),
+            Value::new(2_000_000, MultiAsset::new()),
+            None,
+            script,
+        ))
+    }
+
+    #[test]
+    fn native_script_cbor() {
+        let script = native_script(1);
+        assert_eq!(
+            script_type_and_cbor(&Script::new_native(script.clone())),
+            (ScriptType::Native, script.to_cbor_bytes())
+        );
+    }
+
+    #[test]
+    fn only_created_outputs_are_linked() {
+        let regular = Script::new_native(native_script(1));
+        let collateral = Script::new_native(native_script(2));
+        let created = vec![
+            (0, output(None)),
+            (1, output(Some(regular.clone()))),
+            (2, output(Some(collateral.clone()))),
+        ];
+
+        // valid tx: the collateral return isn't created
+        let valid = reference_scripts(
+            10,
+            created.clone(),
+            &BTreeMap::from([((10, 0), 100), ((10, 1), 101)]),
+        );
+        assert_eq!(valid.len(), 1);
+        assert_eq!(valid[0].0, 101);
+        assert_eq!(valid[0].1.hash(), regular.hash());
+
+        // failed tx: only the collateral return is created
+        let failed = reference_scripts(10, created, &BTreeMap::from([((10, 2), 102)]));
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, 102);
+        assert_eq!(failed[0].1.hash(), collateral.hash());
+        assert_ne!(regular.hash(), collateral.hash());
+    }
+}
@@ -0,0 +1,740 @@
+use std::collections::BTreeMap;
+
+use super::multiera_address_delegation::MultieraAddressDelegationTask;
+use super::multiera_balances::{block_balance_changes, BalanceOwner, MultieraBalanceTask};
+use super::multiera_drep_delegation::MultieraDrepDelegationTask;
+use super::multiera_drep_registration::MultieraDrepRegistrationTask;
+use super::multiera_pool_registration::MultieraPoolRegistrationTask;
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::task_macro::*;
+use crate::era_common::OutputWithTxData;
+use entity::block::EraValue;
+use entity::drep_registration::DrepEvent;
+use entity::sea_orm::{
+    prelude::*, DatabaseTransaction, FromQueryResult, JoinType, QueryOrder, QuerySelect, Set,
+};
+
+const PAGE_SIZE: u64 = 100_000;
+
+carp_task! {
+  name MultieraStakeSnapshotTask;
+  configuration EmptyConfig;
+  doc "Snapshots the stake delegated to each registered pool and drep at the end of every epoch";
+  era multiera;
+  dependencies [
+    MultieraBalanceTask,
+    MultieraAddressDelegationTask,
+    MultieraDrepDelegationTask,
+    MultieraPoolRegistrationTask,
+    MultieraDrepRegistrationTask
+  ];
+  read [multiera_block, multiera_txs, multiera_outputs, multiera_used_inputs_to_outputs_map];
+  write [];
+  should_add_task |_block, _properties| {
+    // whether the block starts a new epoch is only known after looking at the previous block
+    true
+  };
+  execute |previous_data, task| handle_snapshot(
+      task.db_tx,
+      previous_data.multiera_block.as_ref().unwrap(),
+      &previous_data.multiera_txs,
+      &previous_data.multiera_outputs,
+      &previous_data.multiera_used_inputs_to_outputs_map,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+/// Stake of the delegators of a pool or drep
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DelegatedStake {
+    amount: u64,
+    delegator_count: u64,
+}
+
+async fn handle_snapshot(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+    multiera_txs: &[TransactionModel],
+    multiera_outputs: &[TransactionOutputModel],
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Result<(), DbErr> {
+    let previous_block = Block::find()
+        .filter(BlockColumn::Id.lt(database_block.id))
+        .order_by_desc(BlockColumn::Id)
+        .one(db_tx)
+        .await?;
+
+    // the snapshot is the state at the end of the previous epoch
+    // so the transactions of this block (which may already be indexed) are ignored
+    let first_tx_id = multiera_txs.first().map(|tx| tx.id);
+
+    if let Some(previous_block) = previous_block {
+        if previous_block.epoch < database_block.epoch {
+            take_snapshot(
+                db_tx,
+                database_block,
+                previous_block.epoch,
+                first_tx_id,
+                multiera_outputs,
+                multiera_used_inputs_to_outputs_map,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn take_snapshot(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+    epoch: i32,
+    first_tx_id: Option<i64>,
+    multiera_outputs: &[TransactionOutputModel],
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Result<(), DbErr> {
+    let stake = stake_per_credential(
+        db_tx,
+        database_block,
+        multiera_outputs,
+        multiera_used_inputs_to_outputs_map,
+    )
+    .await?;
+
+    let pool_statuses = pool_statuses(db_tx, epoch, first_tx_id).await?;
+    let pool_delegations = active_delegations(
+        latest_pool_delegations(db_tx, first_tx_id).await?,
+        |delegation| {
+            pool_statuses
+                .get(&delegation.target)
+                .is_some_and(|status| status.counts(delegation))
+        },
+    );
+
+    let drep_statuses = drep_statuses(db_tx, first_tx_id).await?;
+    let drep_delegations = active_delegations(
+        latest_drep_delegations(db_tx, first_tx_id).await?,
+        |delegation| {
+            drep_statuses
+                .get(&delegation.target)
+                .is_some_and(|status| status.counts(delegation))
+        },
+    );
+
+    let pool_stake = aggregate_stake(&stake, &pool_delegations);
+    if !pool_stake.is_empty() {
+        PoolStakeSnapshot::insert_many(pool_stake.into_iter().map(|(pool, delegated)| {
+            PoolStakeSnapshotActiveModel {
+                block_id: Set(database_block.id),
+                epoch: Set(epoch),
+                pool_credential: Set(pool),
+                amount: Set(delegated.amount),
+                delegator_count: Set(delegated.delegator_count),
+                ..Default::default()
+            }
+        }))
+        .exec(db_tx)
+        .await?;
+    }
+
+    let drep_stake = aggregate_stake(&stake, &drep_delegations);
+    if !drep_stake.is_empty() {
+        DrepStakeSnapshot::insert_many(drep_stake.into_iter().map(|(drep, delegated)| {
+            DrepStakeSnapshotActiveModel {
+                block_id: Set(database_block.id),
+                epoch: Set(epoch),
+                drep_credential: Set(drep),
+                amount: Set(delegated.amount),
+                delegator_count: Set(delegated.delegator_count),
+                ..Default::default()
+            }
+        }))
+        .exec(db_tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// `stake` and `delegations` are both keyed by stake credential id
+fn aggregate_stake(
+    stake: &BTreeMap<i64, u64>,
+    delegations: &BTreeMap<i64, Vec<u8>>,
+) -> BTreeMap<Vec<u8>, DelegatedStake> {
+    let mut result = BTreeMap::<Vec<u8>, DelegatedStake>::new();
+    for (credential, target) in delegations.iter() {
+        let entry = result.entry(target.clone()).or_default();
+        entry.amount = entry
+            .amount
+            .saturating_add(stake.get(credential).copied().unwrap_or(0));
+        entry.delegator_count += 1;
+    }
+    result
+}
+
+/// Lovelace held by every stake credential at the end of the previous block
+/// (the balances of `StakeCredentialBalance` already include the changes of this block)
+async fn stake_per_credential(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+    multiera_outputs: &[TransactionOutputModel],
+    multiera_used_inputs_to_outputs_map: &BTreeMap<Vec<u8>, BTreeMap<i64, OutputWithTxData>>,
+) -> Result<BTreeMap<i64, u64>, DbErr> {
+    #[derive(FromQueryResult)]
+    pub struct CredentialBalance {
+        id: i64,
+        credential_id: i64,
+        amount: u64,
+    }
+
+    let mut stake = BTreeMap::<i64, u64>::new();
+    let mut last_id = -1;
+    loop {
+        let balances = StakeCredentialBalance::find()
+            .select_only()
+            .column(StakeCredentialBalanceColumn::Id)
+            .column(StakeCredentialBalanceColumn::CredentialId)
+            .column(StakeCredentialBalanceColumn::Amount)
+            .filter(StakeCredentialBalanceColumn::AssetId.is_null())
+            .filter(StakeCredentialBalanceColumn::Id.gt(last_id))
+            .order_by_asc(StakeCredentialBalanceColumn::Id)
+            .limit(PAGE_SIZE)
+            .into_model::<CredentialBalance>()
+            .all(db_tx)
+            .await?;
+
+        stake.extend(
+            balances
+                .iter()
+                .map(|balance| (balance.credential_id, balance.amount)),
+        );
+
+        match balances.last() {
+            Some(balance) if balances.len() as u64 == PAGE_SIZE => last_id = balance.id,
+            _ => break,
+        }
+    }
+
+    let era = EraValue::try_from(database_block.era)
+        .map_err(|_| DbErr::Custom(format!("unknown era {}", database_block.era)))?;
+    let changes = block_balance_changes(
+        db_tx,
+        era,
+        multiera_outputs,
+        multiera_used_inputs_to_outputs_map,
+    )
+    .await?;
+    for (owner, amounts) in changes {
+        let credential_id = match owner {
+            BalanceOwner::StakeCredential(credential_id) => credential_id,
+            BalanceOwner::Address(_) => continue,
+        };
+        let current = stake.get(&credential_id).copied().unwrap_or(0) as i128;
+        let change = amounts.get(&None).copied().unwrap_or(0);
+        let previous = u64::try_from(current - change).map_err(|_| {
+            DbErr::Custom(format!(
+                "invalid stake for credential {credential_id}: {}",
+                current - change
+            ))
+        })?;
+        stake.insert(credential_id, previous);
+    }
+
+    Ok(stake)
+}
+
+#[derive(FromQueryResult)]
+struct Delegation {
+    id: i64,
+    stake_credential: i64,
+    target: Option<Vec<u8>>,
+    tx_id: i64,
+    epoch: i32,
+}
+
+/// Latest delegation of a stake credential
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DelegationTarget {
+    target: Vec<u8>,
+    tx_id: i64,
+    epoch: i32,
+}
+
+/// Adds a page of delegations (sorted by id) to the latest delegation of each credential
+/// Returns the id to continue from, if there may be more delegations
+fn add_delegations(
+    latest: &mut BTreeMap<i64, DelegationTarget>,
+    delegations: Vec<Delegation>,
+) -> Option<i64> {
+    let next_id = match delegations.last() {
+        Some(delegation) if delegations.len() as u64 == PAGE_SIZE => Some(delegation.id),
+        _ => None,
+    };
+    for delegation in delegations {
+        match delegation.target {
+            Some(target) => latest.insert(
+                delegation.stake_credential,
+                DelegationTarget {
+                    target,
+                    tx_id: delegation.tx_id,
+                    epoch: delegation.epoch,
+                },
+            ),
+            // undelegated (ex: deregistered stake key)
+            None => latest.remove(&delegation.stake_credential),
+        };
+    }
+    next_id
+}
+
+/// Keeps the delegations that still count, keyed by stake credential id
+fn active_delegations(
+    latest: BTreeMap<i64, DelegationTarget>,
+    counts: impl Fn(&DelegationTarget) -> bool,
+) -> BTreeMap<i64, Vec<u8>> {
+    latest
+        .into_iter()
+        .filter(|(_, delegation)| counts(delegation))
+        .map(|(credential, delegation)| (credential, delegation.target))
+        .collect()
+}
+
+/// Latest delegation of every credential before `first_tx_id`
+async fn latest_pool_delegations(
+    db_tx: &DatabaseTransaction,
+    first_tx_id: Option<i64>,
+) -> Result<BTreeMap<i64, DelegationTarget>, DbErr> {
+    use entity::stake_delegation::{Column, Entity, Relation};
+
+    let mut latest = BTreeMap::new();
+    let mut last_id = -1;
+    loop {
+        let mut query = Entity::find()
+            .select_only()
+            .column(Column::Id)
+            .column(Column::StakeCredential)
+            .column_as(Column::PoolCredential, "target")
+            .column(Column::TxId)
+            .column(BlockColumn::Epoch)
+            .join(JoinType::InnerJoin, Relation::Transaction.def())
+            .join(JoinType::InnerJoin, TransactionRelation::Block.def())
+            .filter(Column::Id.gt(last_id));
+        if let Some(first_tx_id) = first_tx_id {
+            query = query.filter(Column::TxId.lt(first_tx_id));
+        }
+        let delegations = query
+            .order_by_asc(Column::Id)
+            .limit(PAGE_SIZE)
+            .into_model::<Delegation>()
+            .all(db_tx)
+            .await?;
+
+        match add_delegations(&mut latest, delegations) {
+            Some(id) => last_id = id,
+            None => break,
+        }
+    }
+
+    Ok(latest)
+}
+
+/// Delegations to the predefined "always abstain" and "always no confidence" dreps are not counted
+async fn latest_drep_delegations(
+    db_tx: &DatabaseTransaction,
+    first_tx_id: Option<i64>,
+) -> Result<BTreeMap<i64, DelegationTarget>, DbErr> {
+    use entity::stake_delegation_drep::{Column, Entity, Relation};
+
+    let mut latest = BTreeMap::new();
+    let mut last_id = -1;
+    loop {
+        let mut query = Entity::find()
+            .select_only()
+            .column(Column::Id)
+            .column(Column::StakeCredential)
+            .column_as(Column::DrepCredential, "target")
+            .column(Column::TxId)
+            .column(BlockColumn::Epoch)
+            .join(JoinType::InnerJoin, Relation::Transaction.def())
+            .join(JoinType::InnerJoin, TransactionRelation::Block.def())
+            .filter(Column::Id.gt(last_id));
+        if let Some(first_tx_id) = first_tx_id {
+            query = query.filter(Column::TxId.lt(first_tx_id));
+        }
+        let delegations = query
+            .order_by_asc(Column::Id)
+            .limit(PAGE_SIZE)
+            .into_model::<Delegation>()
+            .all(db_tx)
+            .await?;
+
+        match add_delegations(&mut latest, delegations) {
+            Some(id) => last_id = id,
+            None => break,
+        }
+    }
+
+    Ok(latest)
+}
+
+/// State of a pool at the end of an epoch
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct PoolStatus {
+    registered: bool,
+    /// retirement that didn't take effect yet (a new registration cancels it)
+    pending_retirement: Option<i64>,
+    /// epoch of the last retirement, which removed the delegations made before it
+    retired_epoch: Option<i64>,
+}
+
+impl PoolStatus {
+    /// Retirements take effect at the start of their epoch
+    fn retire_before(&mut self, epoch: i64) {
+        match self.pending_retirement {
+            Some(retiring_epoch) if retiring_epoch <= epoch => {
+                self.registered = false;
+                self.pending_retirement = None;
+                self.retired_epoch = Some(retiring_epoch);
+            }
+            _ => {}
+        }
+    }
+
+    /// `retiring_epoch` is null for registrations (see `PoolRegistration`)
+    fn add_event(&mut self, epoch: i64, retiring_epoch: Option<i64>) {
+        self.retire_before(epoch);
+        match retiring_epoch {
+            None => {
+                self.registered = true;
+                self.pending_retirement = None;
+            }
+            Some(retiring_epoch) => self.pending_retirement = Some(retiring_epoch),
+        }
+    }
+
+    fn counts(&self, delegation: &DelegationTarget) -> bool {
+        self.registered
+            && !matches!(
+                self.retired_epoch,
+                Some(retired_epoch) if (delegation.epoch as i64) < retired_epoch
+            )
+    }
+}
+
+/// Status of every pool at the end of `epoch`
+/// Pools retiring at the start of the next epoch are still part of the snapshot
+async fn pool_statuses(
+    db_tx: &DatabaseTransaction,
+    epoch: i32,
+    first_tx_id: Option<i64>,
+) -> Result<BTreeMap<Vec<u8>, PoolStatus>, DbErr> {
+    #[derive(FromQueryResult)]
+    pub struct PoolEvent {
+        id: i64,
+        pool_credential: Vec<u8>,
+        epoch: i32,
+        retiring_epoch: Option<i64>,
+    }
+
+    let mut statuses = BTreeMap::<Vec<u8>, PoolStatus>::new();
+    let mut last_id = -1;
+    loop {
+        let mut query = PoolRegistration::find()
+            .select_only()
+            .column(PoolRegistrationColumn::Id)
+            .column(PoolRegistrationColumn::PoolCredential)
+            .column(BlockColumn::Epoch)
+            .column(PoolRegistrationColumn::RetiringEpoch)
+            .join(
+                JoinType::InnerJoin,
+                PoolRegistrationRelation::Transaction.def(),
+            )
+            .join(JoinType::InnerJoin, TransactionRelation::Block.def())
+            .filter(PoolRegistrationColumn::Id.gt(last_id));
+        if let Some(first_tx_id) = first_tx_id {
+            query = query.filter(PoolRegistrationColumn::TxId.lt(first_tx_id));
+        }
+        let events = query
+            .order_by_asc(PoolRegistrationColumn::Id)
+            .limit(PAGE_SIZE)
+            .into_model::<PoolEvent>()
+            .all(db_tx)
+            .await?;
+
+        for event in events.iter() {
+            statuses
+                .entry(event.pool_credential.clone())
+                .or_default()
+                .add_event(event.epoch as i64, event.retiring_epoch);
+        }
+
+        match events.last() {
+            Some(event) if events.len() as u64 == PAGE_SIZE => last_id = event.id,
+            _ => break,
+        }
+    }
+
+    for status in statuses.values_mut() {
+        status.retire_before(epoch as i64);
+    }
+    Ok(statuses)
+}
+
+/// State of a drep before the snapshot
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DrepStatus {
+    registered: bool,
+    /// the delegations made before the last retirement were removed by it
+    retired_tx_id: Option<i64>,
+}
+
+impl DrepStatus {
+    fn add_event(&mut self, tx_id: i64, event: i32) {
+        if event == i32::from(DrepEvent::Registration) {
+            self.registered = true;
+        } else if event == i32::from(DrepEvent::Retirement) {
+            self.registered = false;
+            self.retired_tx_id = Some(tx_id);
+        }
+    }
+
+    fn counts(&self, delegation: &DelegationTarget) -> bool {
+        self.registered
+            && !matches!(
+                self.retired_tx_id,
+                Some(retired_tx_id) if delegation.tx_id <= retired_tx_id
+            )
+    }
+}
+
+/// Status of every drep before `first_tx_id`
+async fn drep_statuses(
+    db_tx: &DatabaseTransaction,
+    first_tx_id: Option<i64>,
+) -> Result<BTreeMap<Vec<u8>, DrepStatus>, DbErr> {
+    #[derive(FromQueryResult)]
+    pub struct DrepEventRow {
+        id: i64,
+        drep_credential: Vec<u8>,
+        tx_id: i64,
+        event: i32,
+    }
+
+    let mut statuses = BTreeMap::<Vec<u8>, DrepStatus>::new();
+    let mut last_id = -1;
+    loop {
+        let mut query = DrepRegistration::find()
+            .select_only()
+            .column(DrepRegistrationColumn::Id)
+            .column(DrepRegistrationColumn::DrepCredential)
+            .column(DrepRegistrationColumn::TxId)
+            .column(DrepRegistrationColumn::Event)
+            .filter(DrepRegistrationColumn::Id.gt(last_id));
+        if let Some(first_tx_id) = first_tx_id {
+            query = query.filter(DrepRegistrationColumn::TxId.lt(first_tx_id));
+        }
+        let events = query
+            .order_by_asc(DrepRegistrationColumn::Id)
+            .limit(PAGE_SIZE)
+            .into_model::<DrepEventRow>()
+            .all(db_tx)
+            .await?;
+
+        for event in events.iter() {
+            statuses
+                .entry(event.drep_credential.clone())
+                .or_default()
+                .add_event(event.tx_id, event.event);
+        }
+
+        match events.last() {
+            Some(event) if events.len() as u64 == PAGE_SIZE => last_id = event.id,
+            _ => break,
+        }
+    }
+
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        active_delegations, add_delegations, aggregate_stake, DelegatedStake, Delegation,
+        DelegationTarget, DrepStatus, PoolStatus,
+    };
+    use entity::drep_registration::DrepEvent;
+    use std::collections::BTreeMap;
+
+    fn delegation(target: u8, tx_id: i64, epoch: i32) -> DelegationTarget {
+        DelegationTarget {
+            target: vec![target],
+            tx_id,
+            epoch,
+        }
+    }
+
+    #[test]
+    fn latest_delegation_wins() {
+        let mut latest = BTreeMap::new();
+        let next = add_delegations(
+            &mut latest,
+            vec![
+                Delegation {
+                    id: 1,
+                    stake_credential: 10,
+                    target: Some(vec![1]),
+                    tx_id: 100,
+                    epoch: 1,
+                },
+                Delegation {
+                    id: 2,
+                    stake_credential: 11,
+                    target: Some(vec![1]),
+                    tx_id: 101,
+                    epoch: 1,
+                },
+                Delegation {
+                    id: 3,
+                    stake_credential: 10,
+                    target: Some(vec![2]),
+                    tx_id: 102,
+                    epoch: 2,
+                },
+                Delegation {
+                    id: 4,
+                    stake_credential: 11,
+                    target: None,
+                    tx_id: 103,
+                    epoch: 2,
+                },
+            ],
+        );
+
+        assert_eq!(next, None);
+        assert_eq!(latest, BTreeMap::from([(10, delegation(2, 102, 2))]));
+    }
+
+    #[test]
+    fn aggregate_per_target() {
+        let stake = BTreeMap::from([(10, 100), (11, 50)]);
+        let delegations = BTreeMap::from([(10, vec![1]), (11, vec![1]), (12, vec![2])]);
+
+        assert_eq!(
+            aggregate_stake(&stake, &delegations),
+            BTreeMap::from([
+                (
+                    vec![1],
+                    DelegatedStake {
+                        amount: 150,
+                        delegator_count: 2,
+                    }
+                ),
+                (
+                    vec![2],
+                    DelegatedStake {
+                        amount: 0,
+                        delegator_count: 1,
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn retired_pool() {
+        let mut status = PoolStatus::default();
+        status.add_event(1, None);
+        status.add_event(2, Some(4));
+
+        // still registered until the start of the retiring epoch
+        status.retire_before(3);
+        assert!(status.counts(&delegation(1, 100, 1)));
+        status.retire_before(4);
+        assert!(!status.counts(&delegation(1, 100, 1)));
+    }
+
+    #[test]
+    fn cancelled_pool_retirement() {
+        let mut status = PoolStatus::default();
+        status.add_event(1, None);
+        status.add_event(2, Some(4));
+        status.add_event(3, None);
+
+        status.retire_before(5);
+        assert!(status.counts(&delegation(1, 100, 1)));
+    }
+
+    #[test]
+    fn reregistered_pool() {
+        let mut status = PoolStatus::default();
+        status.add_event(1, None);
+        status.add_event(2, Some(3));
+        status.add_event(5, None);
+
+        status.retire_before(6);
+        // delegations were removed when the pool retired
+        assert!(!status.counts(&delegation(1, 100, 1)));
+        assert!(status.counts(&delegation(1, 200, 5)));
+    }
+
+    #[test]
+    fn redelegation_to_reregistered_target() {
+        // the delegation tasks record a re-delegation to the same target if it retired in between
+        let mut latest = BTreeMap::new();
+        add_delegations(
+            &mut latest,
+            vec![
+                Delegation {
+                    id: 1,
+                    stake_credential: 10,
+                    target: Some(vec![1]),
+                    tx_id: 100,
+                    epoch: 1,
+                },
+                Delegation {
+                    id: 2,
+                    stake_credential: 10,
+                    target: Some(vec![1]),
+                    tx_id: 300,
+                    epoch: 6,
+                },
+            ],
+        );
+
+        let mut pool = PoolStatus::default();
+        pool.add_event(1, None);
+        pool.add_event(2, Some(3));
+        pool.add_event(5, None);
+        pool.retire_before(7);
+        assert!(pool.counts(&latest[&10]));
+
+        let mut drep = DrepStatus::default();
+        drep.add_event(50, DrepEvent::Registration.into());
+        drep.add_event(200, DrepEvent::Retirement.into());
+        drep.add_event(250, DrepEvent::Registration.into());
+        assert!(drep.counts(&latest[&10]));
+    }
+
+    #[test]
+    fn unregistered_drep() {
+        let mut status = DrepStatus::default();
+        status.add_event(100, DrepEvent::Registration.into());
+        assert!(status.counts(&delegation(1, 101, 1)));
+
+        status.add_event(102, DrepEvent::Retirement.into());
+        assert!(!status.counts(&delegation(1, 101, 1)));
+
+        status.add_event(103, DrepEvent::Registration.into());
+        assert!(!status.counts(&delegation(1, 101, 1)));
+        assert!(status.counts(&delegation(1, 104, 1)));
+    }
+
+    #[test]
+    fn inactive_delegations_are_removed() {
+        let latest = BTreeMap::from([(10, delegation(1, 100, 1)), (11, delegation(2, 101, 1))]);
+
+        assert_eq!(
+            active_delegations(latest, |delegation| delegation.target == vec![1]),
+            BTreeMap::from([(10, vec![1])])
+        );
+    }
+}
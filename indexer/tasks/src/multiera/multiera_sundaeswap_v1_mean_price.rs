@@ -1,11 +1,11 @@
 use super::dex::common::{handle_mean_price, DexType};
 use super::multiera_address::MultieraAddressTask;
-use crate::config::EmptyConfig::EmptyConfig;
+use crate::config::DexConfig::DexConfig;
 use crate::dsl::task_macro::*;
 
 carp_task! {
   name MultieraSundaeSwapV1MeanPriceTask;
-  configuration EmptyConfig;
+  configuration DexConfig;
   doc "Adds SundaeSwap V1 mean price updates to the database";
   era multiera;
   dependencies [MultieraAddressTask];
@@ -20,6 +20,7 @@ carp_task! {
       &previous_data.multiera_txs,
       &previous_data.multiera_addresses,
       DexType::SundaeSwapV1,
+      &task.config,
   );
   merge_result |previous_data, _result| {
   };
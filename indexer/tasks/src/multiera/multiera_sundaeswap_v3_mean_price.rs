@@ -0,0 +1,27 @@
+use super::dex::common::{handle_mean_price, DexType};
+use super::multiera_address::MultieraAddressTask;
+use crate::config::DexConfig::DexConfig;
+use crate::dsl::task_macro::*;
+
+carp_task! {
+  name MultieraSundaeSwapV3MeanPriceTask;
+  configuration DexConfig;
+  doc "Adds SundaeSwap V3 mean price updates to the database";
+  era multiera;
+  dependencies [MultieraAddressTask];
+  read [multiera_txs, multiera_addresses];
+  write [];
+  should_add_task |block, _properties| {
+    block.1.transaction_bodies().iter().any(|tx| !tx.outputs().is_empty())
+  };
+  execute |previous_data, task| handle_mean_price(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+      &previous_data.multiera_addresses,
+      DexType::SundaeSwapV3,
+      &task.config,
+  );
+  merge_result |previous_data, _result| {
+  };
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use super::multiera_address_credential_relations::MultieraAddressCredentialRelationTask;
+use super::multiera_used_inputs::MultieraUsedInputTask;
+use super::multiera_used_outputs::MultieraOutputTask;
+use super::utils::common::unspent_before;
+use crate::config::UnspentOutputConfig::UnspentOutputConfig;
+use crate::dsl::task_macro::*;
+use crate::task_ranges::task_processed_height;
+use crate::types::AddressCredentialRelationValue;
+use entity::block::EraValue;
+use entity::sea_orm::sea_query::Expr;
+use entity::sea_orm::{
+    prelude::*, DatabaseTransaction, FromQueryResult, JoinType, QueryOrder, QuerySelect, Set,
+};
+
+const PAGE_SIZE: u64 = 100_000;
+
+carp_task! {
+  name MultieraUnspentOutputTask;
+  configuration UnspentOutputConfig;
+  doc "Keeps a table of the unspent outputs for fast UTxO lookups by address or stake credential";
+  era multiera;
+  dependencies [MultieraOutputTask, MultieraUsedInputTask, MultieraAddressCredentialRelationTask];
+  read [multiera_block, multiera_txs, multiera_outputs, multiera_used_inputs];
+  write [];
+  should_add_task |_block, _properties| {
+    // spent outputs have to be pruned even if the block has no transaction
+    true
+  };
+  execute |previous_data, task| handle_unspent_outputs(
+      task.db_tx,
+      previous_data.multiera_block.as_ref().unwrap(),
+      &previous_data.multiera_txs,
+      &previous_data.multiera_outputs,
+      &previous_data.multiera_used_inputs,
+      task.config.prune_depth,
+  );
+  merge_result |_previous_data, _result| {
+  };
+}
+
+async fn handle_unspent_outputs(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+    multiera_txs: &[TransactionModel],
+    multiera_outputs: &[TransactionOutputModel],
+    multiera_used_inputs: &[TransactionInputModel],
+    prune_depth: u64,
+) -> Result<(), DbErr> {
+    // 1) Add the outputs created while this task didn't run (ex: genesis, Byron or before it was added)
+    if !processed_previous_block(db_tx, database_block).await? {
+        let first_tx_id = multiera_txs.first().map(|tx| tx.id);
+        backfill_unspent_outputs(db_tx, first_tx_id).await?;
+    }
+
+    // 2) Add the outputs of the block
+    if !multiera_outputs.is_empty() {
+        let stake_keys = get_stake_keys(
+            db_tx,
+            multiera_outputs.iter().map(|output| output.address_id),
+        )
+        .await?;
+        insert_unspent_outputs(
+            db_tx,
+            multiera_outputs
+                .iter()
+                .map(|output| (output.id, output.address_id)),
+            &stake_keys,
+        )
+        .await?;
+    }
+
+    // 3) Mark the outputs spent by the block
+    let mut spent_per_tx = BTreeMap::<i64, Vec<i64>>::new();
+    for input in multiera_used_inputs.iter() {
+        spent_per_tx
+            .entry(input.tx_id)
+            .or_default()
+            .push(input.utxo_id);
+    }
+    for (tx_id, output_ids) in spent_per_tx {
+        UnspentOutput::update_many()
+            .col_expr(UnspentOutputColumn::SpentTxId, Expr::value(tx_id))
+            .filter(UnspentOutputColumn::OutputId.is_in(output_ids))
+            .exec(db_tx)
+            .await?;
+    }
+
+    // 4) Remove the outputs spent too long ago to be restored by a rollback
+    prune_spent_outputs(db_tx, database_block, prune_depth).await
+}
+
+/// Whether the previous block was processed by this task, according to the block ranges recorded for it
+/// note: the indexer and the backfill util record the blocks of a range before processing them
+async fn processed_previous_block(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+) -> Result<bool, DbErr> {
+    let previous_block = Block::find()
+        .filter(BlockColumn::Id.lt(database_block.id))
+        .order_by_desc(BlockColumn::Id)
+        .one(db_tx)
+        .await?;
+    match previous_block {
+        // the task only runs on multiera blocks
+        Some(block) if block.era != i32::from(EraValue::Byron) => {
+            task_processed_height(
+                db_tx,
+                nameof::name_of_type!(MultieraUnspentOutputTask),
+                block.height,
+            )
+            .await
+        }
+        _ => Ok(false),
+    }
+}
+
+/// address id -> stake credential id (only for base addresses)
+async fn get_stake_keys(
+    db_tx: &DatabaseTransaction,
+    address_ids: impl Iterator<Item = i64>,
+) -> Result<BTreeMap<i64, i64>, DbErr> {
+    Ok(AddressCredential::find()
+        .filter(AddressCredentialColumn::AddressId.is_in(address_ids))
+        .filter(
+            AddressCredentialColumn::Relation
+                .eq(i32::from(AddressCredentialRelationValue::StakeKey)),
+        )
+        .all(db_tx)
+        .await?
+        .into_iter()
+        .map(|relation| (relation.address_id, relation.credential_id))
+        .collect())
+}
+
+async fn insert_unspent_outputs(
+    db_tx: &DatabaseTransaction,
+    outputs: impl Iterator<Item = (i64 /* output id */, i64 /* address id */)>,
+    stake_keys: &BTreeMap<i64, i64>,
+) -> Result<(), DbErr> {
+    let outputs = outputs
+        .map(|(output_id, address_id)| UnspentOutputActiveModel {
+            output_id: Set(output_id),
+            address_id: Set(address_id),
+            stake_credential_id: Set(stake_keys.get(&address_id).copied()),
+            spent_tx_id: Set(None),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    if !outputs.is_empty() {
+        UnspentOutput::insert_many(outputs).exec(db_tx).await?;
+    }
+    Ok(())
+}
+
+/// Rebuilds the table from the outputs created before `first_tx_id`
+async fn backfill_unspent_outputs(
+    db_tx: &DatabaseTransaction,
+    first_tx_id: Option<i64>,
+) -> Result<(), DbErr> {
+    #[derive(FromQueryResult)]
+    pub struct OutputAddress {
+        id: i64,
+        address_id: i64,
+    }
+
+    // rows left by a previous run of the task miss the blocks it didn't process
+    UnspentOutput::delete_many().exec(db_tx).await?;
+
+    let mut last_id = -1;
+    loop {
+        let mut query = TransactionOutput::find()
+            .select_only()
+            .column(TransactionOutputColumn::Id)
+            .column(TransactionOutputColumn::AddressId)
+            .join(
+                JoinType::LeftJoin,
+                TransactionOutputRelation::TransactionInput.def(),
+            )
+            .filter(unspent_before(first_tx_id))
+            .filter(TransactionOutputColumn::Id.gt(last_id));
+        if let Some(first_tx_id) = first_tx_id {
+            query = query.filter(TransactionOutputColumn::TxId.lt(first_tx_id));
+        }
+        let outputs = query
+            .order_by_asc(TransactionOutputColumn::Id)
+            .limit(PAGE_SIZE)
+            .into_model::<OutputAddress>()
+            .all(db_tx)
+            .await?;
+
+        let stake_keys =
+            get_stake_keys(db_tx, outputs.iter().map(|output| output.address_id)).await?;
+        insert_unspent_outputs(
+            db_tx,
+            outputs.iter().map(|output| (output.id, output.address_id)),
+            &stake_keys,
+        )
+        .await?;
+
+        match outputs.last() {
+            Some(output) if outputs.len() as u64 == PAGE_SIZE => last_id = output.id,
+            _ => break,
+        }
+    }
+
+    Ok(())
+}
+
+async fn prune_spent_outputs(
+    db_tx: &DatabaseTransaction,
+    database_block: &BlockModel,
+    prune_depth: u64,
+) -> Result<(), DbErr> {
+    let max_height = database_block.height as i64 - prune_depth as i64;
+    if max_height < 0 {
+        return Ok(());
+    }
+
+    // transaction ids only increase, so every transaction up to this one is deep enough
+    let last_deep_tx = Transaction::find()
+        .join(JoinType::InnerJoin, TransactionRelation::Block.def())
+        .filter(BlockColumn::Height.lte(max_height))
+        .order_by_desc(TransactionColumn::Id)
+        .one(db_tx)
+        .await?;
+    if let Some(last_deep_tx) = last_deep_tx {
+        UnspentOutput::delete_many()
+            .filter(UnspentOutputColumn::SpentTxId.lte(last_deep_tx.id))
+            .exec(db_tx)
+            .await?;
+    }
+
+    Ok(())
+}
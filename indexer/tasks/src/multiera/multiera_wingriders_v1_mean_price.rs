@@ -2,14 +2,14 @@ use super::utils::common::{
     get_asset_amount, get_plutus_datum_for_output, get_shelley_payment_hash,
 };
 use super::{multiera_address::MultieraAddressTask, utils::common::asset_from_pair};
-use crate::config::EmptyConfig::EmptyConfig;
+use crate::config::DexConfig::DexConfig;
 use crate::multiera::dex::common::{handle_mean_price, DexType};
 
 use crate::dsl::task_macro::*;
 
 carp_task! {
   name MultieraWingRidersV1MeanPriceTask;
-  configuration EmptyConfig;
+  configuration DexConfig;
   doc "Adds WingRiders V1 mean price updates to the database";
   era multiera;
   dependencies [MultieraAddressTask];
@@ -24,6 +24,7 @@ carp_task! {
       &previous_data.multiera_txs,
       &previous_data.multiera_addresses,
       DexType::WingRidersV1,
+      &task.config,
   );
   merge_result |previous_data, _result| {
   };
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use entity::{
+    prelude::*,
+    sea_orm::{prelude::*, DatabaseTransaction, Set},
+};
+
+use super::multiera_address::MultieraAddressTask;
+use super::multiera_stake_credentials::MultieraStakeCredentialTask;
+use crate::config::EmptyConfig::EmptyConfig;
+use crate::dsl::database_task::BlockGlobalInfo;
+use crate::dsl::task_macro::*;
+use crate::era_common::{get_truncated_address, AddressInBlock};
+
+carp_task! {
+  name MultieraWithdrawalTask;
+  configuration EmptyConfig;
+  doc "Tracks the amounts withdrawn from reward addresses";
+  era multiera;
+  dependencies [MultieraAddressTask, MultieraStakeCredentialTask];
+  read [multiera_txs, multiera_addresses, multiera_stake_credential];
+  write [];
+  should_add_task |block, _properties| {
+    block
+      .1
+      .transaction_bodies()
+      .iter()
+      .any(|x| x.withdrawals().is_some())
+  };
+  execute |previous_data, task| handle(
+      task.db_tx,
+      task.block,
+      &previous_data.multiera_txs,
+      &previous_data.multiera_addresses,
+      &previous_data.multiera_stake_credential,
+  );
+  merge_result |_previous_data, _result| {};
+}
+
+async fn handle(
+    db_tx: &DatabaseTransaction,
+    block: BlockInfo<'_, cml_multi_era::MultiEraBlock, BlockGlobalInfo>,
+    multiera_txs: &[TransactionModel],
+    multiera_addresses: &BTreeMap<Vec<u8>, AddressInBlock>,
+    multiera_stake_credential: &BTreeMap<Vec<u8>, StakeCredentialModel>,
+) -> Result<(), DbErr> {
+    let mut queued_withdrawals = vec![];
+
+    for (tx_body, cardano_transaction) in block.1.transaction_bodies().iter().zip(multiera_txs) {
+        // withdrawals only happen if the transaction succeeds
+        if !cardano_transaction.is_valid {
+            continue;
+        }
+        let withdrawals = match tx_body.withdrawals() {
+            None => continue,
+            Some(withdrawals) => withdrawals,
+        };
+        for (reward_address, amount) in withdrawals.iter() {
+            let address = reward_address.clone().to_address().to_raw_bytes();
+            let address_id = multiera_addresses
+                .get(get_truncated_address(&address))
+                .unwrap()
+                .model
+                .id;
+            let stake_credential_id = multiera_stake_credential
+                .get(&reward_address.payment.to_cbor_bytes())
+                .unwrap()
+                .id;
+
+            queued_withdrawals.push(WithdrawalActiveModel {
+                tx_id: Set(cardano_transaction.id),
+                address_id: Set(address_id),
+                stake_credential_id: Set(stake_credential_id),
+                amount: Set(*amount),
+                ..Default::default()
+            });
+        }
+    }
+
+    if !queued_withdrawals.is_empty() {
+        Withdrawal::insert_many(queued_withdrawals)
+            .exec(db_tx)
+            .await?;
+    }
+
+    Ok(())
+}
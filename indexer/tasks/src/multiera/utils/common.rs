@@ -107,19 +107,36 @@ pub async fn asset_from_pair(
 }
 
 pub fn output_from_bytes(utxo: &OutputWithTxData) -> Result<MultiEraTransactionOutput, DbErr> {
-    let output = match utxo.era {
+    output_from_payload(&utxo.model.payload, utxo.era)
+}
+
+pub fn output_from_payload(
+    payload: &[u8],
+    era: EraValue,
+) -> Result<MultiEraTransactionOutput, DbErr> {
+    let output = match era {
         EraValue::Byron => MultiEraTransactionOutput::Byron(
-            cml_chain::byron::ByronTxOut::from_cbor_bytes(&utxo.model.payload).map_err(|err| {
+            cml_chain::byron::ByronTxOut::from_cbor_bytes(payload).map_err(|err| {
                 DbErr::Custom(format!("can't decode byron output payload: {err}"))
             })?,
         ),
         _ => MultiEraTransactionOutput::Shelley(
-            cml_chain::transaction::TransactionOutput::from_cbor_bytes(&utxo.model.payload)
-                .map_err(|err| {
-                    DbErr::Custom(format!("can't decode shelley output payload: {err}"))
-                })?,
+            cml_chain::transaction::TransactionOutput::from_cbor_bytes(payload).map_err(|err| {
+                DbErr::Custom(format!("can't decode shelley output payload: {err}"))
+            })?,
         ),
     };
 
     Ok(output)
 }
+
+/// Condition on a `TransactionOutput` left-joined with its `TransactionInput`
+/// that keeps the outputs that were unspent right before `first_tx_id` (or right now if None)
+pub fn unspent_before(first_tx_id: Option<i64>) -> Condition {
+    match first_tx_id {
+        Some(first_tx_id) => Condition::any()
+            .add(TransactionInputColumn::Id.is_null())
+            .add(TransactionInputColumn::TxId.gte(first_tx_id)),
+        None => Condition::all().add(TransactionInputColumn::Id.is_null()),
+    }
+}
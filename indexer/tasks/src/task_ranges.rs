@@ -0,0 +1,274 @@
+//! Keeps the `TaskBlockRange` table up to date: the blocks each task was run on,
+//! with the configuration and version it had
+
+use crate::execution_plan::ExecutionPlan;
+use crate::utils::{blake2b256, find_task_registry_entry};
+use entity::{
+    prelude::*,
+    sea_orm::{prelude::*, sea_query::Expr, DatabaseTransaction, QueryOrder, Set},
+};
+
+fn config_hash(config: &toml::Value) -> Vec<u8> {
+    blake2b256(config.to_string().as_bytes()).to_vec()
+}
+
+fn task_version(task_name: &str) -> i32 {
+    // note: tasks of a validated plan are always registered
+    find_task_registry_entry(task_name)
+        .map(|entry| entry.get_version())
+        .unwrap_or(1)
+}
+
+/// Removes the blocks after `next_height - 1` from the ranges (ex: after a rollback)
+pub async fn shrink_task_ranges(
+    db_tx: &DatabaseTransaction,
+    next_height: i32,
+) -> Result<(), DbErr> {
+    TaskBlockRange::delete_many()
+        .filter(TaskBlockRangeColumn::StartHeight.gte(next_height))
+        .exec(db_tx)
+        .await?;
+    TaskBlockRange::update_many()
+        .col_expr(
+            TaskBlockRangeColumn::EndHeight,
+            Expr::value(next_height - 1),
+        )
+        .filter(TaskBlockRangeColumn::EndHeight.gte(next_height))
+        .exec(db_tx)
+        .await?;
+    Ok(())
+}
+
+/// Picks the range of every task of the plan that the blocks from `next_height` extend.
+/// The range of a task continues as long as its configuration and version stay the same,
+/// otherwise a new range starts at `next_height`
+pub async fn start_task_ranges(
+    db_tx: &DatabaseTransaction,
+    exec_plan: &ExecutionPlan,
+    next_height: i32,
+) -> Result<Vec<i32>, DbErr> {
+    let mut range_ids = vec![];
+    for (task_name, config) in exec_plan.0.iter() {
+        let config_hash = config_hash(config);
+        let version = task_version(task_name);
+        // note: ranges added by a backfill can be more recent than the ones of the indexer
+        let latest_range = TaskBlockRange::find()
+            .filter(TaskBlockRangeColumn::TaskName.eq(task_name.clone()))
+            .order_by_desc(TaskBlockRangeColumn::StartHeight)
+            .one(db_tx)
+            .await?;
+        let id = match latest_range {
+            Some(range)
+                if range.config_hash == config_hash
+                    && range.version == version
+                    && range.end_height == Some(next_height - 1) =>
+            {
+                range.id
+            }
+            _ => {
+                TaskBlockRangeActiveModel {
+                    task_name: Set(task_name.clone()),
+                    config_hash: Set(config_hash),
+                    version: Set(version),
+                    start_height: Set(next_height),
+                    end_height: Set(None),
+                    ..Default::default()
+                }
+                .insert(db_tx)
+                .await?
+                .id
+            }
+        };
+        range_ids.push(id);
+    }
+    Ok(range_ids)
+}
+
+/// Extends the ranges to the block at `height`.
+/// This happens before the tasks process the block so that they see it as processed
+pub async fn extend_task_ranges(
+    db_tx: &DatabaseTransaction,
+    range_ids: &[i32],
+    height: i32,
+) -> Result<(), DbErr> {
+    if range_ids.is_empty() {
+        return Ok(());
+    }
+    TaskBlockRange::update_many()
+        .col_expr(TaskBlockRangeColumn::EndHeight, Expr::value(height))
+        .filter(TaskBlockRangeColumn::Id.is_in(range_ids.to_vec()))
+        .exec(db_tx)
+        .await?;
+    Ok(())
+}
+
+/// Adds the blocks from `start_height` to `end_height` to the ranges of a task run outside of the
+/// indexer (ex: by the backfill util).
+/// The ranges with the same configuration and version touching these blocks are merged into one
+pub async fn add_task_range(
+    db_tx: &DatabaseTransaction,
+    task_name: &str,
+    config: &toml::Value,
+    start_height: i32,
+    end_height: i32,
+) -> Result<(), DbErr> {
+    let config_hash = config_hash(config);
+    let version = task_version(task_name);
+    let ranges = TaskBlockRange::find()
+        .filter(TaskBlockRangeColumn::TaskName.eq(task_name))
+        .filter(TaskBlockRangeColumn::ConfigHash.eq(config_hash.clone()))
+        .filter(TaskBlockRangeColumn::Version.eq(version))
+        .all(db_tx)
+        .await?;
+
+    let merged = merge_ranges(&ranges, start_height, end_height);
+    if !merged.replaced.is_empty() {
+        TaskBlockRange::delete_many()
+            .filter(TaskBlockRangeColumn::Id.is_in(merged.replaced))
+            .exec(db_tx)
+            .await?;
+    }
+    let mut range = TaskBlockRangeActiveModel {
+        task_name: Set(task_name.to_string()),
+        config_hash: Set(config_hash),
+        version: Set(version),
+        start_height: Set(merged.start_height),
+        end_height: Set(merged.end_height),
+        ..Default::default()
+    };
+    match merged.id {
+        Some(id) => {
+            range.id = Set(id);
+            range.update(db_tx).await?;
+        }
+        None => {
+            range.insert(db_tx).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether the task processed the block at `height`
+pub async fn task_processed_height(
+    db_tx: &DatabaseTransaction,
+    task_name: &str,
+    height: i32,
+) -> Result<bool, DbErr> {
+    let range = TaskBlockRange::find()
+        .filter(TaskBlockRangeColumn::TaskName.eq(task_name))
+        .filter(TaskBlockRangeColumn::StartHeight.lte(height))
+        .filter(TaskBlockRangeColumn::EndHeight.gte(height))
+        .one(db_tx)
+        .await?;
+    Ok(range.is_some())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct MergedRange {
+    /// range kept to store the merged range (None if there is none to merge with)
+    id: Option<i32>,
+    /// ranges merged into the one kept
+    replaced: Vec<i32>,
+    start_height: i32,
+    end_height: Option<i32>,
+}
+
+/// `ranges` are the ranges of the task with the same configuration and version
+fn merge_ranges(ranges: &[TaskBlockRangeModel], start_height: i32, end_height: i32) -> MergedRange {
+    let mut touching: Vec<&TaskBlockRangeModel> = ranges
+        .iter()
+        .filter(|range| {
+            // a range without an end doesn't contain any block yet
+            let range_end = range.end_height.unwrap_or(range.start_height - 1);
+            range.start_height <= end_height + 1 && range_end + 1 >= start_height
+        })
+        .collect();
+    // the most recent range is kept so that the range extended by the indexer keeps its id
+    touching.sort_by_key(|range| range.start_height);
+    let kept = touching.pop();
+
+    MergedRange {
+        id: kept.map(|range| range.id),
+        replaced: touching.iter().map(|range| range.id).collect(),
+        start_height: touching
+            .iter()
+            .chain(kept.iter())
+            .map(|range| range.start_height)
+            .fold(start_height, std::cmp::min),
+        end_height: touching
+            .iter()
+            .chain(kept.iter())
+            .map(|range| range.end_height)
+            .fold(Some(end_height), std::cmp::max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_ranges, MergedRange};
+    use entity::prelude::TaskBlockRangeModel;
+
+    fn range(id: i32, start_height: i32, end_height: Option<i32>) -> TaskBlockRangeModel {
+        TaskBlockRangeModel {
+            id,
+            task_name: "MultieraUnspentOutputTask".to_string(),
+            config_hash: vec![],
+            version: 1,
+            start_height,
+            end_height,
+        }
+    }
+
+    #[test]
+    fn new_range() {
+        assert_eq!(
+            merge_ranges(&[range(1, 500, Some(600))], 100, 199),
+            MergedRange {
+                id: None,
+                replaced: vec![],
+                start_height: 100,
+                end_height: Some(199),
+            }
+        );
+    }
+
+    #[test]
+    fn extends_the_previous_batch() {
+        assert_eq!(
+            merge_ranges(&[range(1, 100, Some(199))], 200, 299),
+            MergedRange {
+                id: Some(1),
+                replaced: vec![],
+                start_height: 100,
+                end_height: Some(299),
+            }
+        );
+    }
+
+    #[test]
+    fn joins_the_range_of_the_indexer() {
+        // the indexer started running the task at height 300 but didn't process any block yet
+        assert_eq!(
+            merge_ranges(&[range(1, 100, Some(199)), range(2, 300, None)], 200, 299),
+            MergedRange {
+                id: Some(2),
+                replaced: vec![1],
+                start_height: 100,
+                end_height: Some(299),
+            }
+        );
+        assert_eq!(
+            merge_ranges(
+                &[range(1, 100, Some(199)), range(2, 300, Some(450))],
+                200,
+                299
+            ),
+            MergedRange {
+                id: Some(2),
+                replaced: vec![1],
+                start_height: 100,
+                end_height: Some(450),
+            }
+        );
+    }
+}
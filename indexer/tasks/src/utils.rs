@@ -38,6 +38,81 @@ impl TaskPerfAggregator {
     pub fn add_to_total(&mut self, duration: &Duration) {
         self.update(TaskPerfAggregator::TOTAL_TIME, *duration);
     }
+
+    pub fn merge(&mut self, other: &TaskPerfAggregator) {
+        for (task, duration) in other.0.iter() {
+            self.update(task, *duration);
+        }
+    }
+}
+
+/// Parameters needed to convert a slot number into a wall-clock time
+#[derive(Debug, Clone, Copy)]
+pub struct SlotTimeParams {
+    /// unix time of slot 0
+    pub system_start: u64,
+    pub byron_slot_length: u64,
+    /// first slot of the Shelley era (0 if the network never had a Byron era)
+    pub shelley_start_slot: u64,
+    pub shelley_slot_length: u64,
+}
+
+impl SlotTimeParams {
+    /// None if the network is unknown (ex: a custom network)
+    pub fn for_network(network: &str) -> Option<Self> {
+        match network {
+            "mainnet" => Some(Self {
+                system_start: 1506203091,
+                byron_slot_length: 20,
+                shelley_start_slot: 4492800,
+                shelley_slot_length: 1,
+            }),
+            "testnet" => Some(Self {
+                system_start: 1563999616,
+                byron_slot_length: 20,
+                shelley_start_slot: 1598400,
+                shelley_slot_length: 1,
+            }),
+            "preprod" => Some(Self {
+                system_start: 1654041600,
+                byron_slot_length: 20,
+                shelley_start_slot: 86400,
+                shelley_slot_length: 1,
+            }),
+            "preview" => Some(Self {
+                system_start: 1666656000,
+                byron_slot_length: 20,
+                shelley_start_slot: 0,
+                shelley_slot_length: 1,
+            }),
+            "sanchonet" => Some(Self {
+                system_start: 1686789000,
+                byron_slot_length: 20,
+                shelley_start_slot: 0,
+                shelley_slot_length: 1,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn slot_to_unix_time(&self, slot: u64) -> u64 {
+        let byron_slots = std::cmp::min(slot, self.shelley_start_slot);
+        let shelley_slots = slot - byron_slots;
+        self.system_start
+            + byron_slots * self.byron_slot_length
+            + shelley_slots * self.shelley_slot_length
+    }
+
+    /// First slot at or after the unix time `time`
+    pub fn unix_time_to_slot(&self, time: u64) -> u64 {
+        let byron_end = self.system_start + self.shelley_start_slot * self.byron_slot_length;
+        if time < byron_end {
+            time.saturating_sub(self.system_start)
+                .div_ceil(self.byron_slot_length)
+        } else {
+            self.shelley_start_slot + (time - byron_end).div_ceil(self.shelley_slot_length)
+        }
+    }
 }
 
 pub fn find_task_registry_entry(task_name: &str) -> Option<TaskRegistryEntry> {
@@ -26,6 +26,9 @@ export function valueToDex(dex: string) {
         case '0': return Dex.WingRiders;
         case '1': return Dex.SundaeSwap;
         case '2': return Dex.MinSwap;
+        case '3': return Dex.MinSwapV2;
+        case '4': return Dex.SundaeSwapV3;
+        case '5': return Dex.WingRidersV2;
     }
     throw new Error(`Unsupported dex: '${dex}'`);
 }
@@ -35,5 +38,8 @@ export function dexToValue(dex: Dex) {
         case Dex.WingRiders: return '0';
         case Dex.SundaeSwap: return '1';
         case Dex.MinSwap: return '2';
+        case Dex.MinSwapV2: return '3';
+        case Dex.SundaeSwapV3: return '4';
+        case Dex.WingRidersV2: return '5';
     }
 }
\ No newline at end of file
@@ -109,6 +109,9 @@ export enum Dex {
   WingRiders = 'WingRiders',
   SundaeSwap = 'SundaeSwap',
   MinSwap = 'MinSwap',
+  MinSwapV2 = 'MinSwapV2',
+  SundaeSwapV3 = 'SundaeSwapV3',
+  WingRidersV2 = 'WingRidersV2',
 };
 
 export type Asset = {